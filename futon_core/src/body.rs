@@ -1,9 +1,15 @@
-use std::string::FromUtf8Error;
+use std::{
+    fmt::Debug,
+    pin::Pin,
+    string::FromUtf8Error,
+    task::{Context, Poll},
+};
 
-use hyper::{body::Bytes, Body};
+use bytes::Bytes;
+use futures::{stream::BoxStream, Stream, StreamExt};
 use serde::{de::DeserializeOwned, Serialize};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FutonBody {
     inner: Bytes,
 }
@@ -48,9 +54,10 @@ impl AsRef<[u8]> for FutonBody {
     }
 }
 
-impl From<FutonBody> for Body {
+#[cfg(feature = "hyper")]
+impl From<FutonBody> for hyper::Body {
     fn from(body: FutonBody) -> Self {
-        Body::from(body.inner)
+        hyper::Body::from(body.inner)
     }
 }
 
@@ -59,3 +66,45 @@ impl From<Bytes> for FutonBody {
         Self { inner }
     }
 }
+
+pub struct FutonBodyStream {
+    inner: BoxStream<'static, Result<Bytes, crate::Error>>,
+}
+
+impl FutonBodyStream {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, crate::Error>> + Send + 'static,
+    {
+        Self {
+            inner: stream.boxed(),
+        }
+    }
+}
+
+impl Debug for FutonBodyStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FutonBodyStream").finish_non_exhaustive()
+    }
+}
+
+impl Stream for FutonBodyStream {
+    type Item = Result<Bytes, crate::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.poll_next_unpin(cx)
+    }
+}
+
+impl From<FutonBody> for FutonBodyStream {
+    fn from(body: FutonBody) -> Self {
+        Self::new(futures::stream::once(async move { Ok(body.inner) }))
+    }
+}
+
+#[cfg(feature = "hyper")]
+impl From<hyper::Body> for FutonBodyStream {
+    fn from(body: hyper::Body) -> Self {
+        Self::new(futures::TryStreamExt::map_err(body, Into::into))
+    }
+}
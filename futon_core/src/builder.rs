@@ -0,0 +1,186 @@
+use std::time::Duration;
+
+use hyper::client::{connect::Connect, HttpConnector};
+
+#[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+use crate::connector::TlsConfig;
+use crate::{connector, Error, FutonClient, Proxy, ProxyConnector};
+
+/// Configures the connection pool, timeouts and TLS settings of a
+/// [`FutonClient`].
+#[derive(Debug, Clone, Default)]
+pub struct FutonClientBuilder {
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    pool_idle_timeout: Option<Option<Duration>>,
+    pool_max_idle_per_host: Option<usize>,
+    http2_only: bool,
+    proxy: Option<Proxy>,
+    proxy_from_env: bool,
+    #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+    tls: TlsConfig,
+}
+
+impl FutonClientBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails requests that haven't received a response within `timeout`.
+    ///
+    /// For buffered responses this includes reading the body, streaming
+    /// responses are only timed until their headers arrive.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Fails connections not established within `timeout`, which also bounds
+    /// the handshake with a proxy once connected to it.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// How long idle connections are kept in the pool, `None` keeps them
+    /// around until the server closes them.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Only speak HTTP/2, without negotiating it first.
+    pub fn http2_only(mut self, enabled: bool) -> Self {
+        self.http2_only = enabled;
+        self
+    }
+
+    /// Connects through `proxy`, with CONNECT tunnelling for HTTP proxies.
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    /// Connects through the proxy set in `HTTPS_PROXY`, if any, bypassing it
+    /// for the hosts in `NO_PROXY`. An explicit [`proxy`](Self::proxy) takes
+    /// precedence.
+    pub fn proxy_from_env(mut self) -> Self {
+        self.proxy_from_env = true;
+        self
+    }
+
+    /// Trusts the PEM encoded certificates in addition to the system roots.
+    #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+    pub fn add_root_certificate(mut self, pem: impl Into<Vec<u8>>) -> Self {
+        self.tls.root_certificates.push(pem.into());
+        self
+    }
+
+    /// Whether the system root certificates are trusted, which they are by
+    /// default.
+    #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+    pub fn native_roots(mut self, enabled: bool) -> Self {
+        self.tls.native_roots = enabled;
+        self
+    }
+
+    /// Authenticates with a PEM encoded certificate chain and PKCS#8 private
+    /// key for mutual TLS.
+    #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+    pub fn identity(mut self, cert: impl Into<Vec<u8>>, key: impl Into<Vec<u8>>) -> Self {
+        self.tls.identity = Some((cert.into(), key.into()));
+        self
+    }
+
+    pub fn build(self) -> Result<FutonClient, Error> {
+        let mut http = HttpConnector::new();
+        http.set_connect_timeout(self.connect_timeout);
+        #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+        http.enforce_http(false);
+
+        let proxy = match self.proxy.clone() {
+            Some(proxy) => Some(proxy),
+            None if self.proxy_from_env => Proxy::from_env()?,
+            None => None,
+        };
+        let tcp = ProxyConnector::new(http, proxy, self.connect_timeout);
+
+        #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+        let connector = connector::connector(tcp, &self.tls)?;
+        #[cfg(not(any(feature = "tls-rustls", feature = "tls-openssl")))]
+        let connector = connector::connector(tcp)?;
+
+        Ok(self.build_with_connector(connector))
+    }
+
+    /// Builds a client establishing its connections through `connector`, such
+    /// as a [`UnixConnector`](crate::UnixConnector).
+    ///
+    /// The connect timeout, proxy and TLS settings only apply to the built-in
+    /// connector and are ignored.
+    pub fn build_with_connector<C>(self, connector: C) -> FutonClient<C>
+    where
+        C: Connect + Clone,
+    {
+        let mut builder = hyper::Client::builder();
+        builder.http2_only(self.http2_only);
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder.pool_max_idle_per_host(max);
+        }
+
+        FutonClient {
+            inner: builder.build(connector),
+            timeout: self.timeout,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::net::TcpListener;
+    use tower::Service;
+    use url::Url;
+
+    use super::*;
+    use crate::FutonRequest;
+
+    #[tokio::test]
+    async fn it_times_out_requests() -> anyhow::Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = Url::parse(&format!("http://{}/", listener.local_addr()?))?;
+        let server = tokio::spawn(async move {
+            let (socket, _) = listener.accept().await?;
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            drop(socket);
+            std::io::Result::Ok(())
+        });
+
+        let mut client = FutonClientBuilder::new()
+            .timeout(Duration::from_millis(50))
+            .pool_max_idle_per_host(1)
+            .build()?;
+        let err = client.call(FutonRequest::new(url)?).await.unwrap_err();
+        assert!(matches!(err, Error::Timeout(timeout) if timeout == Duration::from_millis(50)));
+
+        server.abort();
+        Ok(())
+    }
+
+    #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+    #[test]
+    fn it_rejects_an_invalid_client_identity() {
+        let res = FutonClientBuilder::new()
+            .native_roots(false)
+            .identity("not a certificate", "not a key")
+            .build();
+        assert!(matches!(res, Err(Error::Tls(_))));
+    }
+}
@@ -0,0 +1,254 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use futures::future::BoxFuture;
+use http::{header::IF_NONE_MATCH, HeaderMap, Method, StatusCode, Version};
+use tower::{Layer, Service};
+
+use crate::{Error, FutonRequest, FutonResponse};
+
+/// An in-memory cache of `GET` responses, keyed by URL and revalidated through their ETag.
+///
+/// Every cached response is still revalidated with the server, so the cache never
+/// serves stale data; it saves transferring and parsing unchanged bodies.
+///
+/// The cache is a [`Layer`] wrapping the transport requests are sent through.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    inner: Arc<Mutex<Entries>>,
+}
+
+#[derive(Debug)]
+struct Entries {
+    capacity: usize,
+    entries: HashMap<String, CachedResponse>,
+    order: VecDeque<String>,
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct CachedResponse {
+    etag: String,
+    version: Version,
+    headers: HeaderMap,
+    body: Bytes,
+}
+
+impl CachedResponse {
+    pub(crate) fn etag(&self) -> &str {
+        &self.etag
+    }
+
+    pub(crate) fn into_response(self) -> FutonResponse {
+        FutonResponse::new(StatusCode::OK, self.headers, self.body.into())
+            .with_version(self.version)
+    }
+}
+
+impl ResponseCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(Entries {
+                capacity,
+                entries: HashMap::new(),
+                order: VecDeque::new(),
+            })),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn invalidate(&self, url: &str) {
+        let mut entries = self.lock();
+        entries.entries.remove(url);
+        entries.order.retain(|key| key != url);
+    }
+
+    pub fn clear(&self) {
+        let mut entries = self.lock();
+        entries.entries.clear();
+        entries.order.clear();
+    }
+
+    pub(crate) fn is_cacheable(req: &FutonRequest) -> bool {
+        req.method == Method::GET && !req.streaming && !req.has_header(IF_NONE_MATCH)
+    }
+
+    pub(crate) fn get(&self, url: &str) -> Option<CachedResponse> {
+        self.lock().entries.get(url).cloned()
+    }
+
+    pub(crate) fn store(&self, url: String, res: &FutonResponse) {
+        let etag = match res.etag() {
+            Some(etag) if res.status() == StatusCode::OK => etag.to_string(),
+            _ => return,
+        };
+
+        let cached = CachedResponse {
+            etag,
+            version: res.version(),
+            headers: res.headers().clone(),
+            body: res.body().bytes(),
+        };
+
+        let mut entries = self.lock();
+        if entries.capacity == 0 {
+            return;
+        }
+
+        if entries.entries.insert(url.clone(), cached).is_none() {
+            entries.order.push_back(url);
+            while entries.order.len() > entries.capacity {
+                if let Some(oldest) = entries.order.pop_front() {
+                    entries.entries.remove(&oldest);
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.inner.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<S> Layer<S> for ResponseCache {
+    type Service = CacheService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        CacheService {
+            inner,
+            cache: self.clone(),
+        }
+    }
+}
+
+/// The service created by the [`ResponseCache`] layer.
+#[derive(Clone, Debug)]
+pub struct CacheService<S> {
+    inner: S,
+    cache: ResponseCache,
+}
+
+impl<S> Service<FutonRequest> for CacheService<S>
+where
+    S: Service<FutonRequest, Response = FutonResponse, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = BoxFuture<'static, Result<FutonResponse, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        if !ResponseCache::is_cacheable(&req) {
+            return Box::pin(self.inner.call(req));
+        }
+
+        let key = req.url.to_string();
+        let cached = self.cache.get(&key);
+        let req = match cached {
+            Some(ref cached) => req.header(IF_NONE_MATCH, cached.etag()),
+            None => Ok(req),
+        };
+        let fut = req.map(|req| self.inner.call(req));
+        let cache = self.cache.clone();
+        Box::pin(async move {
+            let res = fut?.await?;
+            match cached {
+                Some(cached) if res.is_not_modified() => {
+                    tracing::debug!(etag = cached.etag(), "serving cached response");
+                    Ok(cached.into_response())
+                }
+                _ => {
+                    cache.store(key, &res);
+                    Ok(res)
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn response(etag: &str, body: &'static [u8]) -> FutonResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(http::header::ETAG, etag.parse().unwrap());
+        FutonResponse::new(StatusCode::OK, headers, Bytes::from_static(body).into())
+    }
+
+    #[test]
+    fn it_caches_responses_with_an_etag() {
+        let cache = ResponseCache::new(2);
+        cache.store("http://a".to_string(), &response("\"1-a\"", b"a"));
+        cache.store(
+            "http://b".to_string(),
+            &FutonResponse::new(StatusCode::OK, HeaderMap::new(), Bytes::new().into()),
+        );
+
+        assert_eq!(cache.len(), 1);
+        let cached = cache.get("http://a").unwrap();
+        assert_eq!(cached.etag(), "\"1-a\"");
+
+        let res = cached.into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.body().as_ref(), b"a");
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_entries() {
+        let cache = ResponseCache::new(2);
+        cache.store("http://a".to_string(), &response("\"1-a\"", b"a"));
+        cache.store("http://b".to_string(), &response("\"1-b\"", b"b"));
+        cache.store("http://c".to_string(), &response("\"1-c\"", b"c"));
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("http://a").is_none());
+        assert!(cache.get("http://c").is_some());
+
+        cache.invalidate("http://c");
+        assert!(cache.get("http://c").is_none());
+    }
+
+    #[tokio::test]
+    async fn it_revalidates_cached_responses() -> anyhow::Result<()> {
+        let service = tower::service_fn(|req: FutonRequest| async move {
+            let res = match req.headers().get(IF_NONE_MATCH) {
+                Some(etag) if etag == "\"1-a\"" => FutonResponse::new(
+                    StatusCode::NOT_MODIFIED,
+                    HeaderMap::new(),
+                    Bytes::new().into(),
+                ),
+                _ => response("\"1-a\"", b"a"),
+            };
+            Result::<_, Error>::Ok(res)
+        });
+        let cache = ResponseCache::new(1);
+        let mut service = cache.layer(service);
+
+        let res = service.call(FutonRequest::new("http://a")?).await?;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(cache.len(), 1);
+
+        let res = service.call(FutonRequest::new("http://a")?).await?;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.body().as_ref(), b"a");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,90 @@
+use std::{future::Future, pin::Pin, time::Duration};
+
+use http::Response;
+use hyper::client::connect::Connect;
+use tower::Service;
+use tracing::Instrument;
+
+use crate::{Connector, Error, FutonClientBuilder, FutonRequest, FutonResponse};
+
+/// The default transport, sending requests to CouchDB through hyper.
+///
+/// The connector defaults to the one picked by the enabled TLS feature, see
+/// [`FutonClientBuilder::build_with_connector`] to use another one.
+#[derive(Clone)]
+pub struct FutonClient<C = Connector> {
+    pub(crate) inner: hyper::Client<C>,
+    pub(crate) timeout: Option<Duration>,
+}
+
+impl FutonClient {
+    pub fn builder() -> FutonClientBuilder {
+        FutonClientBuilder::new()
+    }
+}
+
+impl Default for FutonClient {
+    fn default() -> Self {
+        FutonClientBuilder::new()
+            .build()
+            .expect("failed to build the default client")
+    }
+}
+
+impl<C> Service<FutonRequest> for FutonClient<C>
+where
+    C: Connect + Clone + Send + Sync + 'static,
+{
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(
+        &mut self,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    #[tracing::instrument(skip(self, req), fields(url=%req.url, method=%req.method))]
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        let inner = self.inner.clone();
+        let mut client = std::mem::replace(&mut self.inner, inner);
+        let timeout = self.timeout;
+        let fut = async move {
+            let streaming = req.streaming;
+            let res = client.call(req.try_into()?).await?;
+            let (parts, body) = res.into_parts();
+            if streaming && parts.status.is_success() {
+                let res = FutonResponse::streaming(parts.status, parts.headers, body.into())
+                    .with_version(parts.version);
+                tracing::debug!(status = %res.status(), "streaming response");
+                return Ok(res);
+            }
+
+            let body = hyper::body::to_bytes(body).await?;
+            let res = Response::from_parts(parts, body);
+            let res = FutonResponse::try_from(res)?;
+            tracing::debug!(
+                status = %res.status(),
+                request_id = res.request_id(),
+                ?res,
+                "request completed"
+            );
+            Ok(res)
+        };
+        Box::pin(
+            async move {
+                match timeout {
+                    Some(timeout) => tokio::time::timeout(timeout, fut)
+                        .await
+                        .map_err(|_| Error::Timeout(timeout))?,
+                    None => fut.await,
+                }
+            }
+            .instrument(tracing::debug_span!("request")),
+        )
+    }
+}
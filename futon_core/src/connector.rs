@@ -0,0 +1,112 @@
+//! The hyper connector used by [`FutonClient`](crate::FutonClient), chosen by
+//! the enabled TLS feature.
+//!
+//! `tls-rustls` takes precedence when both TLS features are enabled. Without
+//! either, the client only speaks plain HTTP.
+
+use crate::ProxyConnector;
+
+#[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+use crate::Error;
+
+#[cfg(feature = "tls-rustls")]
+pub type Connector = hyper_rustls::HttpsConnector<ProxyConnector>;
+
+#[cfg(all(feature = "tls-openssl", not(feature = "tls-rustls")))]
+pub type Connector = hyper_tls::HttpsConnector<ProxyConnector>;
+
+#[cfg(not(any(feature = "tls-rustls", feature = "tls-openssl")))]
+pub type Connector = ProxyConnector;
+
+/// PEM encoded certificates and keys handed to the TLS backend.
+#[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+#[derive(Debug, Clone)]
+pub(crate) struct TlsConfig {
+    pub(crate) native_roots: bool,
+    pub(crate) root_certificates: Vec<Vec<u8>>,
+    pub(crate) identity: Option<(Vec<u8>, Vec<u8>)>,
+}
+
+#[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+impl Default for TlsConfig {
+    fn default() -> Self {
+        Self {
+            native_roots: true,
+            root_certificates: Vec::new(),
+            identity: None,
+        }
+    }
+}
+
+#[cfg(feature = "tls-rustls")]
+pub(crate) fn connector(tcp: ProxyConnector, tls: &TlsConfig) -> Result<Connector, Error> {
+    use rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore};
+    use rustls_pemfile::Item;
+
+    let mut roots = RootCertStore::empty();
+    if tls.native_roots {
+        let native = rustls_native_certs::load_native_certs().map_err(Error::tls)?;
+        let native: Vec<_> = native.into_iter().map(|cert| cert.0).collect();
+        roots.add_parsable_certificates(&native);
+    }
+    for pem in &tls.root_certificates {
+        for der in rustls_pemfile::certs(&mut pem.as_slice()).map_err(Error::tls)? {
+            roots.add(&Certificate(der)).map_err(Error::tls)?;
+        }
+    }
+
+    let config = ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots);
+    let config = match &tls.identity {
+        Some((cert, key)) => {
+            let certs = rustls_pemfile::certs(&mut cert.as_slice())
+                .map_err(Error::tls)?
+                .into_iter()
+                .map(Certificate)
+                .collect();
+            let key = rustls_pemfile::read_all(&mut key.as_slice())
+                .map_err(Error::tls)?
+                .into_iter()
+                .find_map(|item| match item {
+                    Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(key),
+                    _ => None,
+                })
+                .ok_or_else(|| Error::tls("no private key found in client identity"))?;
+            config
+                .with_single_cert(certs, PrivateKey(key))
+                .map_err(Error::tls)?
+        }
+        None => config.with_no_client_auth(),
+    };
+
+    let https = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config(config)
+        .https_or_http()
+        .enable_http1()
+        .enable_http2()
+        .wrap_connector(tcp);
+    Ok(https)
+}
+
+#[cfg(all(feature = "tls-openssl", not(feature = "tls-rustls")))]
+pub(crate) fn connector(tcp: ProxyConnector, tls: &TlsConfig) -> Result<Connector, Error> {
+    use native_tls::{Certificate, Identity, TlsConnector};
+
+    let mut builder = TlsConnector::builder();
+    builder.disable_built_in_roots(!tls.native_roots);
+    for pem in &tls.root_certificates {
+        builder.add_root_certificate(Certificate::from_pem(pem).map_err(Error::tls)?);
+    }
+    if let Some((cert, key)) = &tls.identity {
+        builder.identity(Identity::from_pkcs8(cert, key).map_err(Error::tls)?);
+    }
+    let tls = builder.build().map_err(Error::tls)?;
+
+    Ok(hyper_tls::HttpsConnector::from((tcp, tls.into())))
+}
+
+#[cfg(not(any(feature = "tls-rustls", feature = "tls-openssl")))]
+pub(crate) fn connector(tcp: ProxyConnector) -> Result<Connector, crate::Error> {
+    Ok(tcp)
+}
@@ -1,9 +1,10 @@
 use http::{header::HeaderName, HeaderValue};
 use secstr::SecUtf8;
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub enum Credentials {
     Basic(String, SecUtf8),
+    #[default]
     None,
 }
 
@@ -29,9 +30,3 @@ impl Credentials {
         }
     }
 }
-
-impl Default for Credentials {
-    fn default() -> Self {
-        Self::None
-    }
-}
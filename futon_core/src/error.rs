@@ -1,15 +1,66 @@
-use crate::{request::RequestError, response::ErrorResponse};
+use std::time::Duration;
+
+use http::StatusCode;
+
+use crate::{request::RequestError, response::ErrorResponse, BoxError};
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     #[error("http protocol error: {0}")]
     Http(#[from] http::Error),
+    #[cfg(feature = "hyper")]
     #[error("http transport error: {0}")]
     Hyper(#[from] hyper::Error),
+    #[error("transport error: {0}")]
+    Transport(#[source] BoxError),
     #[error("json error: {0}")]
     Json(#[from] serde_json::Error),
     #[error("failed to construct HTTP request: {0}")]
     RequestError(#[from] RequestError),
     #[error("{0}")]
     CouchError(#[from] ErrorResponse),
+    #[error("request timed out after {0:?}")]
+    Timeout(Duration),
+    #[error("tls configuration error: {0}")]
+    Tls(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Error {
+    /// Whether the request failed for a transient reason, such as a dropped
+    /// connection, a timeout, or an overloaded server.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            #[cfg(feature = "hyper")]
+            Self::Hyper(err) => {
+                err.is_connect()
+                    || err.is_timeout()
+                    || err.is_closed()
+                    || err.is_incomplete_message()
+            }
+            Self::Timeout(_) => true,
+            Self::CouchError(err) => matches!(
+                err.status,
+                StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::TOO_MANY_REQUESTS
+                    | StatusCode::BAD_GATEWAY
+                    | StatusCode::SERVICE_UNAVAILABLE
+                    | StatusCode::GATEWAY_TIMEOUT
+            ),
+            _ => false,
+        }
+    }
+
+    /// Recovers errors raised by futon itself from a boxed service error,
+    /// keeping any other error as [`Error::Transport`].
+    pub fn from_boxed(err: BoxError) -> Self {
+        match err.downcast::<Self>() {
+            Ok(err) => *err,
+            Err(err) => Self::Transport(err),
+        }
+    }
+
+    #[cfg(any(feature = "tls-rustls", feature = "tls-openssl"))]
+    pub(crate) fn tls(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> Self {
+        Self::Tls(err.into())
+    }
 }
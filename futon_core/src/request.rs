@@ -1,5 +1,5 @@
+use bytes::Bytes;
 use http::{header::HeaderName, HeaderMap, HeaderValue, Method, Request};
-use hyper::{body::Bytes, Body};
 use serde::Serialize;
 use std::{
     borrow::Borrow,
@@ -10,10 +10,13 @@ use url::Url;
 
 use crate::{Credentials, FutonBody};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct FutonRequest {
     pub(crate) url: Url,
     pub(crate) method: Method,
+    pub(crate) streaming: bool,
+    /// The database targeted and how many path segments lead up to it.
+    database: Option<(String, usize)>,
     credentials: Credentials,
     headers: HeaderMap,
     body: FutonBody,
@@ -58,6 +61,8 @@ impl FutonRequest {
             credentials: Credentials::default(),
             headers: HeaderMap::default(),
             method: Method::default(),
+            streaming: false,
+            database: None,
             body: FutonBody::default(),
         })
     }
@@ -88,13 +93,17 @@ impl FutonRequest {
         K: AsRef<str>,
         V: AsRef<str>,
     {
-        self.url.query_pairs_mut().extend_pairs(params);
+        let mut params = params.into_iter().peekable();
+        if params.peek().is_some() {
+            self.url.query_pairs_mut().extend_pairs(params);
+        }
         self
     }
 
     pub fn query_string<Q: Serialize>(mut self, query: &Q) -> Result<Self, RequestError> {
         let qs = serde_qs::to_string(query)?;
-        self.url.set_query(Some(&qs));
+        self.url
+            .set_query(Some(qs.as_str()).filter(|qs| !qs.is_empty()));
         Ok(self)
     }
 
@@ -111,6 +120,10 @@ impl FutonRequest {
         Ok(self)
     }
 
+    pub(crate) fn has_header(&self, key: HeaderName) -> bool {
+        self.headers.contains_key(key)
+    }
+
     pub fn body(mut self, body: Bytes) -> Self {
         self.body = body.into();
         self
@@ -121,18 +134,77 @@ impl FutonRequest {
         self.header(http::header::CONTENT_TYPE, "application/json")
     }
 
+    /// Asks the client not to buffer a successful response body, so that it can be
+    /// consumed incrementally through [`FutonResponse::into_stream`](crate::FutonResponse::into_stream).
+    pub fn streaming(mut self) -> Self {
+        self.streaming = true;
+        self
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.streaming
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub fn http_method(&self) -> &Method {
+        &self.method
+    }
+
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Appends the `/` separated segments of `path` to the base URL's path.
     pub fn path(mut self, path: &str) -> Self {
-        self.url.set_path(path);
+        let mut path = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .peekable();
+        {
+            let mut segments = self.url.path_segments_mut().unwrap();
+            if path.peek().is_some() {
+                segments.pop_if_empty().extend(path);
+            } else {
+                // Keep addressing the root of the base path, e.g. `/couchdb/`.
+                segments.pop_if_empty().push("");
+            }
+        }
+        self
+    }
+
+    pub fn segment(mut self, segment: &str) -> Self {
+        self.url
+            .path_segments_mut()
+            .unwrap()
+            .pop_if_empty()
+            .push(segment);
+        self
+    }
+
+    pub fn database(mut self, db: &str) -> Self {
+        self = self.segment(db);
+        let depth = self.url.path_segments().map_or(0, Iterator::count);
+        self.database = Some((db.to_string(), depth));
         self
     }
 
-    #[inline]
-    pub fn database(self, db: &str) -> Self {
-        self.path(db)
+    pub fn database_name(&self) -> Option<&str> {
+        self.database.as_ref().map(|(name, _)| name.as_str())
+    }
+
+    /// The percent-encoded path segments following the database, if the
+    /// request targets one.
+    pub fn database_path(&self) -> Option<impl Iterator<Item = &str>> {
+        let (_, depth) = self.database.as_ref()?;
+        let segments = self.url.path_segments()?;
+        Some(segments.skip(*depth).filter(|segment| !segment.is_empty()))
     }
 
     pub fn partition(mut self, partition: &str) -> Self {
-        if self.url.path() == "/" {
+        if self.database.is_none() {
             panic!("cannot construct a partition URL without a database prefix",);
         }
 
@@ -145,11 +217,17 @@ impl FutonRequest {
     }
 
     pub fn document(mut self, id: &str, rev: Option<&str>) -> Self {
-        if self.url.path() == "/" {
+        if self.database.is_none() {
             panic!("cannot construct a document URL without a database prefix",);
         }
 
-        self.url.path_segments_mut().unwrap().push(id);
+        {
+            let mut segments = self.url.path_segments_mut().unwrap();
+            match split_document_id(id) {
+                (Some(prefix), name) => segments.push(prefix).push(name),
+                (None, id) => segments.push(id),
+            };
+        }
         if let Some(rev) = rev {
             self.url.query_pairs_mut().append_pair("rev", rev);
         }
@@ -158,11 +236,25 @@ impl FutonRequest {
     }
 }
 
-impl TryFrom<FutonRequest> for Request<Body> {
+/// Splits the `_design` or `_local` prefix, which CouchDB expects as its own
+/// path segment, from a document id. Any other `/` is part of the id.
+fn split_document_id(id: &str) -> (Option<&str>, &str) {
+    for prefix in ["_design", "_local"] {
+        if let Some(name) = id
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_prefix('/'))
+        {
+            return (Some(prefix), name);
+        }
+    }
+    (None, id)
+}
+
+impl TryFrom<FutonRequest> for Request<Bytes> {
     type Error = crate::Error;
 
     fn try_from(request: FutonRequest) -> Result<Self, Self::Error> {
-        let body = Body::from(request.body);
+        let body = request.body.bytes();
         let mut req = Request::builder()
             .method(request.method)
             .uri(&request.url.to_string())
@@ -178,6 +270,16 @@ impl TryFrom<FutonRequest> for Request<Body> {
     }
 }
 
+#[cfg(feature = "hyper")]
+impl TryFrom<FutonRequest> for Request<hyper::Body> {
+    type Error = crate::Error;
+
+    fn try_from(request: FutonRequest) -> Result<Self, Self::Error> {
+        let req: Request<Bytes> = request.try_into()?;
+        Ok(req.map(hyper::Body::from))
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum RequestError {
     #[error("failed to parse URL: {0}")]
@@ -208,8 +310,8 @@ mod test {
             .credentials(Credentials::basic("hello", "world"))
             .header("test", "header")?
             .method("PUT")?
-            .database("test")?
-            .document("example", None)?
+            .database("test")
+            .document("example", None)
             .json("test")?;
 
         assert_eq!(
@@ -219,9 +321,9 @@ mod test {
         assert_eq!(req.credentials, Credentials::basic("hello", "world"));
         assert_eq!(req.headers.get("test").unwrap(), &"header");
         assert_eq!(req.method, Method::PUT);
-        assert_eq!(req.body.inner.as_ref(), b"\"test\"");
+        assert_eq!(req.body.as_ref(), b"\"test\"");
 
-        let req: Request<Body> = req.try_into()?;
+        let req: Request<Bytes> = req.try_into()?;
         assert_eq!(req.uri(), "https://example.com/test/example");
         assert_eq!(req.method(), Method::PUT);
         assert_eq!(
@@ -232,4 +334,97 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn it_skips_empty_query_params() -> anyhow::Result<()> {
+        let req = FutonRequest::new("https://example.com")?
+            .database("test")
+            .query_params(Vec::<(&str, &str)>::new());
+        assert_eq!(req.url.as_str(), "https://example.com/test");
+
+        let req = req.query_params([("batch", "ok")]);
+        assert_eq!(req.url.as_str(), "https://example.com/test?batch=ok");
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_tracks_the_targeted_database() -> anyhow::Result<()> {
+        let req = FutonRequest::new("https://example.com")?.path("_up");
+        assert!(req.database_name().is_none());
+        assert!(req.database_path().is_none());
+
+        let req = FutonRequest::new("https://example.com")?
+            .database("test")
+            .segment("_design")
+            .segment("app");
+        assert_eq!(req.database_name(), Some("test"));
+        assert_eq!(
+            req.database_path().unwrap().collect::<Vec<_>>(),
+            vec!["_design", "app"]
+        );
+
+        let req = FutonRequest::new("https://example.com")?.database("test");
+        assert_eq!(req.database_path().unwrap().count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_appends_to_the_base_path() -> anyhow::Result<()> {
+        let req = FutonRequest::new("https://gateway/couchdb/")?.path("/");
+        assert_eq!(req.url.as_str(), "https://gateway/couchdb/");
+
+        let req = FutonRequest::new("https://gateway/couchdb/")?.path("_up");
+        assert_eq!(req.url.as_str(), "https://gateway/couchdb/_up");
+
+        let req = FutonRequest::new("https://gateway/couchdb")?
+            .database("films")
+            .partition("sci-fi")
+            .segment("_all_docs");
+        assert_eq!(
+            req.url.as_str(),
+            "https://gateway/couchdb/films/_partition/sci-fi/_all_docs"
+        );
+        assert_eq!(req.database_path().unwrap().count(), 3);
+
+        let req = FutonRequest::new("https://gateway/couchdb/")?
+            .database("films")
+            .document("alien", Some("1-abc"));
+        assert_eq!(
+            req.url.as_str(),
+            "https://gateway/couchdb/films/alien?rev=1-abc"
+        );
+
+        let req = FutonRequest::new("https://gateway/")?.database("media/films");
+        assert_eq!(req.url.as_str(), "https://gateway/media%2Ffilms");
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_encodes_document_ids() -> anyhow::Result<()> {
+        let cases = [
+            ("alien", "/films/alien"),
+            ("sci-fi:alien", "/films/sci-fi:alien"),
+            ("films/alien", "/films/films%2Falien"),
+            ("_design/app", "/films/_design/app"),
+            ("_design/app/v2", "/films/_design/app%2Fv2"),
+            ("_local/checkpoint", "/films/_local/checkpoint"),
+            ("_designer", "/films/_designer"),
+            ("_local", "/films/_local"),
+            ("what?#", "/films/what%3F%23"),
+            ("100% sure", "/films/100%25%20sure"),
+            ("café", "/films/caf%C3%A9"),
+        ];
+
+        for (id, path) in cases {
+            let req = FutonRequest::new("https://example.com")?
+                .database("films")
+                .document(id, None);
+            assert_eq!(req.url.path(), path, "{id}");
+        }
+
+        Ok(())
+    }
 }
@@ -1,20 +1,92 @@
-use http::{Response, StatusCode};
-use hyper::body::Bytes;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{
+    header::{HeaderName, CONTENT_TYPE, ETAG, LOCATION},
+    HeaderMap, Response, StatusCode, Version,
+};
 use serde::Deserialize;
 
-use crate::FutonBody;
+use crate::{FutonBody, FutonBodyStream};
+
+pub const X_COUCH_REQUEST_ID: HeaderName = HeaderName::from_static("x-couch-request-id");
+pub const X_COUCHDB_BODY_TIME: HeaderName = HeaderName::from_static("x-couchdb-body-time");
 
 #[derive(Debug)]
 pub struct FutonResponse {
     status: StatusCode,
+    version: Version,
+    headers: HeaderMap,
     body: FutonBody,
+    stream: Option<FutonBodyStream>,
 }
 
 impl FutonResponse {
+    pub fn new(status: StatusCode, headers: HeaderMap, body: FutonBody) -> Self {
+        Self {
+            status,
+            version: Version::default(),
+            headers,
+            body,
+            stream: None,
+        }
+    }
+
+    pub fn streaming(status: StatusCode, headers: HeaderMap, stream: FutonBodyStream) -> Self {
+        Self {
+            status,
+            version: Version::default(),
+            headers,
+            body: FutonBody::empty(),
+            stream: Some(stream),
+        }
+    }
+
+    pub fn with_version(mut self, version: Version) -> Self {
+        self.version = version;
+        self
+    }
+
     pub fn status(&self) -> StatusCode {
         self.status
     }
 
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    pub fn etag(&self) -> Option<&str> {
+        self.header_str(ETAG)
+    }
+
+    pub fn request_id(&self) -> Option<&str> {
+        self.header_str(X_COUCH_REQUEST_ID)
+    }
+
+    pub fn location(&self) -> Option<&str> {
+        self.header_str(LOCATION)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.header_str(CONTENT_TYPE)
+    }
+
+    /// Time the server spent receiving the request body.
+    pub fn body_time(&self) -> Option<Duration> {
+        self.header_str(X_COUCHDB_BODY_TIME)
+            .and_then(|ms| ms.parse().ok())
+            .map(Duration::from_millis)
+    }
+
+    #[inline]
+    fn header_str(&self, name: HeaderName) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
     pub fn body(&self) -> &FutonBody {
         &self.body
     }
@@ -23,23 +95,41 @@ impl FutonResponse {
         self.body
     }
 
+    pub fn is_streaming(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    pub fn into_stream(self) -> FutonBodyStream {
+        match self.stream {
+            Some(stream) => stream,
+            None => self.body.into(),
+        }
+    }
+
     pub fn is_not_found(&self) -> bool {
         self.status == StatusCode::NOT_FOUND
     }
 
+    pub fn is_not_modified(&self) -> bool {
+        self.status == StatusCode::NOT_MODIFIED
+    }
+
     pub fn error_for_status(self) -> Result<Self, ErrorResponse> {
         let status = self.status;
         if status.is_client_error() || status.is_server_error() {
+            let request_id = self.request_id().map(ToString::to_string);
             let body = self.into_body();
             let err = match body.json::<ErrorResponse>() {
                 Ok(mut err) => {
                     err.status = status;
+                    err.request_id = request_id;
                     err
                 }
                 Err(_) => ErrorResponse {
                     status,
                     error: status.canonical_reason().unwrap_or("unknown error").into(),
                     reason: String::from_utf8_lossy(&body.bytes()).into(),
+                    request_id,
                 },
             };
 
@@ -56,20 +146,20 @@ impl TryFrom<Response<Bytes>> for FutonResponse {
     fn try_from(res: Response<Bytes>) -> Result<Self, Self::Error> {
         let (parts, body) = res.into_parts();
         let body = FutonBody::from(body);
-        Ok(Self {
-            status: parts.status,
-            body,
-        })
+        Ok(Self::new(parts.status, parts.headers, body).with_version(parts.version))
     }
 }
 
 #[derive(Debug, Deserialize, thiserror::Error)]
-#[error("{error}: {reason}")]
+#[error("{error}: {reason}{}", .request_id.as_ref().map(|id| format!(" (request id: {id})")).unwrap_or_default())]
 pub struct ErrorResponse {
     pub error: String,
     pub reason: String,
     #[serde(skip, default)]
     pub status: StatusCode,
+    /// The `X-Couch-Request-ID` of the failed request, to correlate with CouchDB logs.
+    #[serde(skip, default)]
+    pub request_id: Option<String>,
 }
 
 #[cfg(test)]
@@ -80,14 +170,75 @@ mod test {
     fn it_builds_a_futon_response() -> anyhow::Result<()> {
         let res = Response::builder()
             .status(200)
+            .header(ETAG, "\"1-abc\"")
             .body(Bytes::from_static(b"\"hello futon\""))?;
 
         let res = FutonResponse::try_from(res)?;
 
         assert_eq!(res.status, StatusCode::OK);
+        assert_eq!(res.version(), Version::HTTP_11);
+        assert_eq!(res.etag(), Some("\"1-abc\""));
         let body = res.body.json::<String>()?;
         assert_eq!(body.as_str(), "hello futon");
 
         Ok(())
     }
+
+    #[test]
+    fn it_streams_a_buffered_response() -> anyhow::Result<()> {
+        let res = Response::builder()
+            .status(200)
+            .body(Bytes::from_static(b"\"hello futon\""))?;
+
+        let res = FutonResponse::try_from(res)?;
+        assert!(!res.is_streaming());
+
+        let chunks =
+            futures::executor::block_on_stream(res.into_stream()).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(chunks, vec![Bytes::from_static(b"\"hello futon\"")]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_exposes_response_metadata() -> anyhow::Result<()> {
+        let res = Response::builder()
+            .status(201)
+            .version(Version::HTTP_2)
+            .header(CONTENT_TYPE, "application/json")
+            .header(LOCATION, "http://example.com/db/doc")
+            .header(X_COUCH_REQUEST_ID, "a1b2c3")
+            .header(X_COUCHDB_BODY_TIME, "12")
+            .body(Bytes::new())?;
+
+        let res = FutonResponse::try_from(res)?;
+
+        assert_eq!(res.version(), Version::HTTP_2);
+        assert_eq!(res.content_type(), Some("application/json"));
+        assert_eq!(res.location(), Some("http://example.com/db/doc"));
+        assert_eq!(res.request_id(), Some("a1b2c3"));
+        assert_eq!(res.body_time(), Some(Duration::from_millis(12)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn it_keeps_the_request_id_on_errors() -> anyhow::Result<()> {
+        let res = Response::builder()
+            .status(404)
+            .header(X_COUCH_REQUEST_ID, "a1b2c3")
+            .body(Bytes::from_static(
+                br#"{"error":"not_found","reason":"missing"}"#,
+            ))?;
+
+        let err = FutonResponse::try_from(res)?
+            .error_for_status()
+            .unwrap_err();
+
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+        assert_eq!(err.request_id.as_deref(), Some("a1b2c3"));
+        assert_eq!(err.to_string(), "not_found: missing (request id: a1b2c3)");
+
+        Ok(())
+    }
 }
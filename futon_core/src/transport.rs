@@ -0,0 +1,128 @@
+use std::{
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use tower::{util::BoxCloneService, Service, ServiceExt};
+
+use crate::{Error, FutonRequest, FutonResponse};
+
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Any [`Service`] able to send a [`FutonRequest`], with its type erased.
+///
+/// This is what `futon` sends its requests through, so a [`FutonClient`]
+/// can be swapped for another HTTP client, a mock, or a service wrapped in
+/// tower layers.
+///
+/// Each call waits for its own clone of the inner service to become ready,
+/// so the transport is always ready itself and can be called without
+/// [`Service::poll_ready`], including over services with backpressure such
+/// as a concurrency limit.
+///
+/// [`FutonClient`]: crate::FutonClient
+pub struct Transport {
+    // Only ever locked to clone the service, which makes the transport `Sync`
+    // so it can be borrowed across `.await`s.
+    inner: Mutex<BoxCloneService<FutonRequest, FutonResponse, Error>>,
+}
+
+impl Transport {
+    pub fn new<S>(service: S) -> Self
+    where
+        S: Service<FutonRequest, Response = FutonResponse> + Clone + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+    {
+        let service = service.map_err(|err: S::Error| Error::from_boxed(err.into()));
+        Self {
+            inner: Mutex::new(BoxCloneService::new(service)),
+        }
+    }
+
+    fn service(&mut self) -> &mut BoxCloneService<FutonRequest, FutonResponse, Error> {
+        self.inner.get_mut().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl Clone for Transport {
+    fn clone(&self) -> Self {
+        let inner = self.inner.lock().unwrap_or_else(|err| err.into_inner());
+        Self {
+            inner: Mutex::new(inner.clone()),
+        }
+    }
+}
+
+impl std::fmt::Debug for Transport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Transport").finish_non_exhaustive()
+    }
+}
+
+impl Service<FutonRequest> for Transport {
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = BoxFuture<'static, Result<FutonResponse, Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        Box::pin(self.service().clone().oneshot(req))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use bytes::Bytes;
+    use http::{HeaderMap, StatusCode};
+
+    use super::*;
+
+    /// Only accepts calls once `poll_ready` has been called on it, like
+    /// services with backpressure do.
+    #[derive(Clone, Default)]
+    struct Backpressured {
+        ready: bool,
+    }
+
+    impl Service<FutonRequest> for Backpressured {
+        type Response = FutonResponse;
+
+        type Error = Error;
+
+        type Future = futures::future::Ready<Result<FutonResponse, Error>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            self.ready = true;
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _req: FutonRequest) -> Self::Future {
+            assert!(std::mem::take(&mut self.ready), "poll_ready not called");
+            futures::future::ready(Ok(FutonResponse::new(
+                StatusCode::OK,
+                HeaderMap::new(),
+                Bytes::new().into(),
+            )))
+        }
+    }
+
+    #[tokio::test]
+    async fn it_drives_readiness_of_the_inner_service() -> anyhow::Result<()> {
+        let mut transport = Transport::new(Backpressured::default());
+
+        for _ in 0..2 {
+            let req = FutonRequest::new("http://couchdb.invalid/")?;
+            let res = transport.call(req).await?;
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    future::Future,
+    io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use http::Uri;
+use hyper::client::connect::{Connected, Connection};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::UnixStream,
+};
+use tower::Service;
+
+/// A hyper connector reaching CouchDB through a Unix domain socket.
+///
+/// Every connection goes to the socket regardless of the request URL, whose
+/// host is still sent in the `Host` header. No TLS is layered on top, so
+/// requests to `https` URLs are sent unencrypted over the socket.
+#[derive(Debug, Clone)]
+pub struct UnixConnector {
+    path: Arc<PathBuf>,
+}
+
+impl UnixConnector {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: Arc::new(path.as_ref().to_path_buf()),
+        }
+    }
+}
+
+impl Service<Uri> for UnixConnector {
+    type Response = UnixConnection;
+
+    type Error = io::Error;
+
+    type Future = Pin<Box<dyn Future<Output = io::Result<UnixConnection>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let path = Arc::clone(&self.path);
+        Box::pin(async move { Ok(UnixConnection(UnixStream::connect(&*path).await?)) })
+    }
+}
+
+/// A connection established by [`UnixConnector`].
+#[derive(Debug)]
+pub struct UnixConnection(UnixStream);
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for UnixConnection {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::UnixListener,
+    };
+
+    use super::*;
+    use crate::{FutonClientBuilder, FutonRequest};
+
+    #[tokio::test]
+    async fn it_connects_through_a_unix_socket() -> anyhow::Result<()> {
+        let path = std::env::temp_dir().join(format!("futon-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path)?;
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await?;
+            let mut buf = vec![0; 1024];
+            let read = socket.read(&mut buf).await?;
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 11\r\n\r\n{\"ok\":true}")
+                .await?;
+            std::io::Result::Ok(String::from_utf8_lossy(&buf[..read]).into_owned())
+        });
+
+        let mut client = FutonClientBuilder::new().build_with_connector(UnixConnector::new(&path));
+        let res = client
+            .call(FutonRequest::new("http://couchdb/")?.path("_up"))
+            .await?;
+        assert_eq!(res.status(), http::StatusCode::OK);
+        assert_eq!(res.body().as_ref(), b"{\"ok\":true}");
+
+        let request = server.await??;
+        assert!(request.starts_with("GET /_up HTTP/1.1\r\n"));
+        assert!(request.contains("host: couchdb\r\n"));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
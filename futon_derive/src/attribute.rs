@@ -0,0 +1,61 @@
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::Parser, punctuated::Punctuated, Error, Field, Fields, Ident, ItemStruct, Result, Token,
+};
+
+pub(crate) fn expand(args: TokenStream, mut input: ItemStruct) -> Result<TokenStream> {
+    let args = Punctuated::<Ident, Token![,]>::parse_terminated.parse2(args)?;
+
+    let fields = match &mut input.fields {
+        Fields::Named(fields) => &mut fields.named,
+        fields => {
+            return Err(Error::new_spanned(
+                fields,
+                "#[document] can only be used on structs with named fields",
+            ))
+        }
+    };
+
+    for arg in args {
+        if fields
+            .iter()
+            .any(|field| field.ident.as_ref() == Some(&arg))
+        {
+            return Err(Error::new(
+                arg.span(),
+                format!("field `{arg}` already exists"),
+            ));
+        }
+
+        let field = match arg.to_string().as_str() {
+            "deleted" => quote! {
+                #[serde(rename = "_deleted", default, skip_serializing_if = "::std::ops::Not::not")]
+                pub deleted: bool
+            },
+            "attachments" => quote! {
+                #[serde(rename = "_attachments", default, skip_serializing_if = "::std::option::Option::is_none")]
+                pub attachments: ::std::option::Option<
+                    ::futon::__private::serde_json::Map<
+                        ::std::string::String,
+                        ::futon::__private::serde_json::Value,
+                    >,
+                >
+            },
+            "conflicts" => quote! {
+                #[serde(rename = "_conflicts", default, skip_serializing_if = "::std::vec::Vec::is_empty")]
+                pub conflicts: ::std::vec::Vec<::std::string::String>
+            },
+            _ => {
+                return Err(Error::new(
+                    arg.span(),
+                    "expected `deleted`, `attachments` or `conflicts`",
+                ))
+            }
+        };
+
+        fields.push(Field::parse_named.parse2(field)?);
+    }
+
+    Ok(input.into_token_stream())
+}
@@ -0,0 +1,171 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    spanned::Spanned, Data, DeriveInput, Error, Field, Fields, GenericArgument, LitStr,
+    PathArguments, Result, Type,
+};
+
+pub(crate) fn expand(input: DeriveInput) -> Result<TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect::<Vec<_>>(),
+            fields => {
+                return Err(Error::new(
+                    fields.span(),
+                    "Document can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new(
+                input.ident.span(),
+                "Document can only be derived for structs",
+            ))
+        }
+    };
+
+    let id = find_field(&input, &fields, "id")?;
+    let rev = find_field(&input, &fields, "rev")?;
+    check_rename(id, "_id")?;
+    check_rename(rev, "_rev")?;
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let id = &id.ident;
+    let rev_ident = &rev.ident;
+
+    let (get_rev, set_rev) = if is_option(&rev.ty) {
+        (
+            quote!(self.#rev_ident.as_deref()),
+            quote!(self.#rev_ident = ::std::option::Option::Some(rev.to_string())),
+        )
+    } else {
+        (
+            quote!(::std::option::Option::Some(&self.#rev_ident)),
+            quote!(self.#rev_ident = rev.to_string()),
+        )
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::futon::document::Document for #name #ty_generics #where_clause {
+            fn id(&self) -> &str {
+                &self.#id
+            }
+
+            fn rev(&self) -> ::std::option::Option<&str> {
+                #get_rev
+            }
+
+            fn set_id(&mut self, id: impl ::std::string::ToString) -> &mut Self {
+                self.#id = id.to_string();
+                self
+            }
+
+            fn set_rev(&mut self, rev: impl ::std::string::ToString) -> &mut Self {
+                #set_rev;
+                self
+            }
+        }
+    })
+}
+
+fn find_field<'a>(input: &DeriveInput, fields: &[&'a Field], kind: &str) -> Result<&'a Field> {
+    let mut marked = None;
+    for field in fields {
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("futon"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("id") || meta.path.is_ident("rev") {
+                    if meta.path.is_ident(kind) {
+                        if marked.is_some() {
+                            return Err(meta.error(format!("duplicate #[futon({kind})] field")));
+                        }
+                        marked = Some(*field);
+                    }
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `id` or `rev`"))
+                }
+            })?;
+        }
+    }
+
+    marked
+        .or_else(|| {
+            fields
+                .iter()
+                .find(|field| field.ident.as_ref().is_some_and(|ident| ident == kind))
+                .copied()
+        })
+        .ok_or_else(|| {
+            Error::new(
+                input.ident.span(),
+                format!(
+                    "missing `{kind}` field, name it `{kind}` or mark it with #[futon({kind})]"
+                ),
+            )
+        })
+}
+
+fn check_rename(field: &Field, expected: &str) -> Result<()> {
+    let mut serialize = None;
+    let mut deserialize = None;
+
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("serde"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                if meta.input.peek(syn::Token![=]) {
+                    let name = meta.value()?.parse::<LitStr>()?.value();
+                    serialize = Some(name.clone());
+                    deserialize = Some(name);
+                } else {
+                    meta.parse_nested_meta(|inner| {
+                        let name = inner.value()?.parse::<LitStr>()?.value();
+                        if inner.path.is_ident("serialize") {
+                            serialize = Some(name);
+                        } else if inner.path.is_ident("deserialize") {
+                            deserialize = Some(name);
+                        }
+                        Ok(())
+                    })?;
+                }
+            } else if meta.input.peek(syn::Token![=]) {
+                meta.value()?.parse::<syn::Expr>()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                let _content;
+                syn::parenthesized!(_content in meta.input);
+            }
+            Ok(())
+        })?;
+    }
+
+    match (serialize.as_deref(), deserialize.as_deref()) {
+        (Some(ser), Some(de)) if ser == expected && de == expected => Ok(()),
+        _ => Err(Error::new(
+            field.span(),
+            format!("this field must be renamed with #[serde(rename = \"{expected}\")]"),
+        )),
+    }
+}
+
+fn is_option(ty: &Type) -> bool {
+    let Type::Path(path) = ty else {
+        return false;
+    };
+
+    path.path.segments.last().is_some_and(|segment| {
+        segment.ident == "Option"
+            && matches!(
+                &segment.arguments,
+                PathArguments::AngleBracketed(args)
+                    if matches!(args.args.first(), Some(GenericArgument::Type(_)))
+            )
+    })
+}
@@ -0,0 +1,29 @@
+mod attribute;
+mod derive;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, ItemStruct};
+
+/// Implements `futon::document::Document` for a struct.
+///
+/// The id and rev fields are the ones marked with `#[futon(id)]` and `#[futon(rev)]`,
+/// or the ones named `id` and `rev`. They must be renamed to `_id` and `_rev` for serde.
+#[proc_macro_derive(Document, attributes(futon))]
+pub fn derive_document(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive::expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Adds CouchDB special fields to a document struct.
+///
+/// Accepts any of `deleted`, `attachments` and `conflicts`, and must be placed
+/// above the `#[derive]` attribute so that serde sees the added fields.
+#[proc_macro_attribute]
+pub fn document(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as ItemStruct);
+    attribute::expand(args.into(), input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,90 @@
+use futon::document::Document;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Serialize, Deserialize, Document)]
+struct Named {
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+    rev: Option<String>,
+    message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Document)]
+struct Marked {
+    #[futon(id)]
+    #[serde(rename = "_id")]
+    key: String,
+    #[futon(rev)]
+    #[serde(rename = "_rev")]
+    revision: String,
+}
+
+#[futon::document(deleted, attachments, conflicts)]
+#[derive(Debug, Serialize, Deserialize, Document)]
+struct WithSpecialFields {
+    #[serde(rename = "_id")]
+    id: String,
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
+    rev: Option<String>,
+}
+
+#[test]
+fn it_derives_document_from_field_names() {
+    let mut doc = Named {
+        id: "test".to_string(),
+        rev: None,
+        message: "Hello Futon!".to_string(),
+    };
+
+    assert_eq!(doc.id(), "test");
+    assert_eq!(doc.rev(), None);
+
+    doc.set_id("other").set_rev("1-abc");
+    assert_eq!(doc.id(), "other");
+    assert_eq!(doc.rev(), Some("1-abc"));
+    assert_eq!(
+        serde_json::to_value(&doc).unwrap(),
+        json!({ "_id": "other", "_rev": "1-abc", "message": "Hello Futon!" })
+    );
+}
+
+#[test]
+fn it_derives_document_from_marked_fields() {
+    let mut doc: Marked =
+        serde_json::from_value(json!({ "_id": "test", "_rev": "1-abc" })).unwrap();
+
+    assert_eq!(doc.id(), "test");
+    assert_eq!(doc.rev(), Some("1-abc"));
+
+    doc.set_rev("2-def");
+    assert_eq!(&doc.revision, "2-def");
+    assert_eq!(&doc.key, "test");
+}
+
+#[test]
+fn it_adds_special_fields() {
+    let doc: WithSpecialFields = serde_json::from_value(json!({
+        "_id": "test",
+        "_rev": "2-abc",
+        "_deleted": true,
+        "_attachments": { "hello.txt": { "stub": true } },
+        "_conflicts": ["2-def"],
+    }))
+    .unwrap();
+
+    assert_eq!(doc.id(), "test");
+    assert!(doc.deleted);
+    assert!(doc.attachments.unwrap().contains_key("hello.txt"));
+    assert_eq!(doc.conflicts, vec!["2-def".to_string()]);
+
+    let doc: WithSpecialFields = serde_json::from_value(json!({ "_id": "test" })).unwrap();
+    assert!(!doc.deleted);
+    assert!(doc.attachments.is_none());
+    assert!(doc.conflicts.is_empty());
+    assert_eq!(
+        serde_json::to_value(&doc).unwrap(),
+        json!({ "_id": "test" })
+    );
+}
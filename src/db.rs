@@ -1,21 +1,26 @@
-use futon_core::{Credentials, FutonClient, FutonRequest, Service};
-use http::{Method, StatusCode};
+use futon_core::{Credentials, FutonRequest, Service, Transport};
+use http::{header::CONTENT_TYPE, Method, StatusCode};
+use tokio::time::Instant;
 use url::Url;
 
 use crate::{
     ddoc::DesignDocuments,
     document::{Document, Documents},
     error::FutonError,
+    local::LocalDocuments,
     request::{DatabaseCreationParams, ViewParams},
-    response::{DatabaseInfo, Rev, ViewResults},
+    response::{
+        self, ActiveTask, DatabaseInfo, DesignDocumentInfo, MissingRevs, PurgeResult, Rev,
+        RevsDiff, ViewResults,
+    },
     FutonResult,
 };
-use std::fmt::Debug;
+use std::{collections::HashMap, fmt::Debug, time::Duration};
 
 const NAME_REGEX: &str = r#"^[a-z][a-z0-9_$()+/-]*$"#;
 
 pub struct Database {
-    client: FutonClient,
+    client: Transport,
     url: Url,
     name: String,
     credentials: Credentials,
@@ -23,7 +28,7 @@ pub struct Database {
 
 impl Database {
     pub(crate) fn new(
-        client: FutonClient,
+        client: Transport,
         url: Url,
         name: impl ToString,
         credentials: Credentials,
@@ -99,6 +104,277 @@ impl Database {
         Ok(())
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn design_info(&self, ddoc: &str) -> FutonResult<DesignDocumentInfo> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_design")
+            .segment(design_name(ddoc))
+            .segment("_info");
+
+        let res = client.call(req).await?;
+
+        let info = res.error_for_status()?.into_body().json()?;
+        Ok(info)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn compact(&self) -> FutonResult<()> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .header(CONTENT_TYPE, "application/json")?
+            .database(&self.name)
+            .segment("_compact");
+
+        let _: response::Ok = client
+            .call(req)
+            .await?
+            .error_for_status()?
+            .into_body()
+            .json()?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn compact_design(&self, ddoc: &str) -> FutonResult<()> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .header(CONTENT_TYPE, "application/json")?
+            .database(&self.name)
+            .segment("_compact")
+            .segment(design_name(ddoc));
+
+        let _: response::Ok = client
+            .call(req)
+            .await?
+            .error_for_status()?
+            .into_body()
+            .json()?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn view_cleanup(&self) -> FutonResult<()> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .header(CONTENT_TYPE, "application/json")?
+            .database(&self.name)
+            .segment("_view_cleanup");
+
+        let _: response::Ok = client
+            .call(req)
+            .await?
+            .error_for_status()?
+            .into_body()
+            .json()?;
+        Ok(())
+    }
+
+    /// Polls `_active_tasks` every `interval` until it lists no compaction of
+    /// this database, failing with a timeout once `timeout` has elapsed.
+    #[tracing::instrument(skip(self))]
+    pub async fn wait_for_compaction(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+    ) -> FutonResult<()> {
+        self.wait_for_tasks(interval, timeout, |task| task.kind == "database_compaction")
+            .await
+    }
+
+    /// Polls `_active_tasks` every `interval` until it lists no compaction of
+    /// the design document's views, failing with a timeout once `timeout` has
+    /// elapsed.
+    #[tracing::instrument(skip(self))]
+    pub async fn wait_for_design_compaction(
+        &self,
+        ddoc: &str,
+        interval: Duration,
+        timeout: Duration,
+    ) -> FutonResult<()> {
+        let ddoc = format!("_design/{}", design_name(ddoc));
+        self.wait_for_tasks(interval, timeout, |task| {
+            task.kind == "view_compaction" && task.design_document.as_deref() == Some(&ddoc)
+        })
+        .await
+    }
+
+    async fn wait_for_tasks(
+        &self,
+        interval: Duration,
+        timeout: Duration,
+        running: impl Fn(&ActiveTask) -> bool,
+    ) -> FutonResult<()> {
+        let deadline = Instant::now() + timeout;
+        while self.active_tasks().await?.iter().any(&running) {
+            tracing::debug!("compaction still running");
+            sleep_until_next_poll(interval, deadline, timeout).await?;
+        }
+
+        Ok(())
+    }
+
+    /// The tasks running on any shard of this database.
+    async fn active_tasks(&self) -> FutonResult<Vec<ActiveTask>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .path("_active_tasks");
+
+        let tasks: Vec<ActiveTask> = client
+            .call(req)
+            .await?
+            .error_for_status()?
+            .into_body()
+            .json()?;
+        Ok(tasks
+            .into_iter()
+            .filter(|task| task.database.as_deref().map(shard_database) == Some(&self.name))
+            .collect())
+    }
+
+    /// Permanently removes the given revisions of each document, leaving no tombstone behind.
+    #[tracing::instrument(skip(self))]
+    pub async fn purge(&self, revs: HashMap<String, Vec<String>>) -> FutonResult<PurgeResult> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_purge")
+            .json(revs)?;
+
+        let res = client.call(req).await?;
+
+        let result = res.error_for_status()?.into_body().json()?;
+        Ok(result)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn purged_infos_limit(&self) -> FutonResult<usize> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_purged_infos_limit");
+
+        let res = client.call(req).await?;
+
+        let limit = res.error_for_status()?.into_body().json()?;
+        Ok(limit)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn set_purged_infos_limit(&self, limit: usize) -> FutonResult<()> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::PUT)?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_purged_infos_limit")
+            .json(limit)?;
+
+        let _: response::Ok = client
+            .call(req)
+            .await?
+            .error_for_status()?
+            .into_body()
+            .json()?;
+        Ok(())
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn revs_limit(&self) -> FutonResult<usize> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_revs_limit");
+
+        let res = client.call(req).await?;
+
+        let limit = res.error_for_status()?.into_body().json()?;
+        Ok(limit)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn set_revs_limit(&self, limit: usize) -> FutonResult<()> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::PUT)?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_revs_limit")
+            .json(limit)?;
+
+        let _: response::Ok = client
+            .call(req)
+            .await?
+            .error_for_status()?
+            .into_body()
+            .json()?;
+        Ok(())
+    }
+
+    /// Returns, for each document, the given revisions that do not exist in the database.
+    #[tracing::instrument(skip(self))]
+    pub async fn revs_diff(
+        &self,
+        revs: HashMap<String, Vec<String>>,
+    ) -> FutonResult<HashMap<String, RevsDiff>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_revs_diff")
+            .json(revs)?;
+
+        let res = client.call(req).await?;
+
+        let diff = res.error_for_status()?.into_body().json()?;
+        Ok(diff)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn missing_revs(
+        &self,
+        revs: HashMap<String, Vec<String>>,
+    ) -> FutonResult<HashMap<String, Vec<String>>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .database(&self.name)
+            .segment("_missing_revs")
+            .json(revs)?;
+
+        let res = client.call(req).await?;
+
+        let MissingRevs { missing_revs } = res.error_for_status()?.into_body().json()?;
+        Ok(missing_revs)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn all_docs<D: Document + Debug>(
         &self,
@@ -125,6 +401,11 @@ impl Database {
         Documents::new(&self.client, &self.url, &self.name, &self.credentials)
     }
 
+    #[inline]
+    pub fn local_docs(&self) -> LocalDocuments<'_> {
+        LocalDocuments::new(&self.client, &self.url, &self.name, &self.credentials)
+    }
+
     #[inline]
     pub fn design_docs(&self, partition: Option<String>) -> DesignDocuments<'_> {
         DesignDocuments::new(
@@ -136,3 +417,32 @@ impl Database {
         )
     }
 }
+
+#[inline]
+fn design_name(ddoc: &str) -> &str {
+    ddoc.strip_prefix("_design/").unwrap_or(ddoc)
+}
+
+/// The database a shard such as `shards/00000000-7fffffff/films.1681234567`
+/// belongs to. Names without the shard prefix are returned as they are.
+fn shard_database(shard: &str) -> &str {
+    match shard
+        .strip_prefix("shards/")
+        .and_then(|s| s.split_once('/'))
+    {
+        Some((_, name)) => name.rsplit_once('.').map_or(name, |(name, _)| name),
+        None => shard,
+    }
+}
+
+async fn sleep_until_next_poll(
+    interval: Duration,
+    deadline: Instant,
+    timeout: Duration,
+) -> FutonResult<()> {
+    if Instant::now() + interval > deadline {
+        return Err(futon_core::Error::Timeout(timeout).into());
+    }
+    tokio::time::sleep(interval).await;
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use std::{convert::Infallible, fmt::Display, str::FromStr};
 
-use futon_core::{Credentials, FutonClient, FutonRequest, Service};
+use futon_core::{Credentials, FutonRequest, Service, Transport};
 use http::Method;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use url::Url;
@@ -12,19 +12,14 @@ use crate::{
     FutonResult,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub enum QueryServer {
+    #[default]
     JavaScript,
     Erlang,
     Custom(String),
 }
 
-impl Default for QueryServer {
-    fn default() -> Self {
-        Self::JavaScript
-    }
-}
-
 impl FromStr for QueryServer {
     type Err = Infallible;
 
@@ -73,7 +68,9 @@ impl<'de> Deserialize<'de> for QueryServer {
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DesignDocument {
+    #[serde(rename = "_id")]
     id: String,
+    #[serde(rename = "_rev", skip_serializing_if = "Option::is_none")]
     rev: Option<String>,
     language: QueryServer,
 }
@@ -110,7 +107,7 @@ impl Document for DesignDocument {
 }
 
 pub struct DesignDocuments<'db> {
-    client: &'db FutonClient,
+    client: &'db Transport,
     url: &'db Url,
     partition: Option<String>,
     db_name: &'db str,
@@ -119,7 +116,7 @@ pub struct DesignDocuments<'db> {
 
 impl<'db> DesignDocuments<'db> {
     pub fn new(
-        client: &'db FutonClient,
+        client: &'db Transport,
         url: &'db Url,
         partition: Option<String>,
         db_name: &'db str,
@@ -1,10 +1,24 @@
-use futon_core::{Credentials, FutonClient, FutonRequest, Service};
+use futon_core::{Credentials, FutonRequest, Service, Transport};
 use http::{Method, StatusCode};
-use serde::{de::DeserializeOwned, Serialize};
-use std::fmt::Debug;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fmt::Debug,
+    ops::{Deref, DerefMut},
+};
 use url::Url;
 
-use crate::{request::CopyDestination, response::DocumentOperation, FutonResult};
+use crate::{
+    request::{BulkDocsParams, CopyDestination, GetOptions, OpenRevs, RetryPolicy, WriteOptions},
+    response::{
+        BulkDocResult, Conditional, DocumentOperation, FetchedDocument, OpenRev, Tombstone,
+        Updated, WithConflicts,
+    },
+    FutonResult,
+};
+
+#[cfg(feature = "derive")]
+pub use futon_derive::Document;
 
 pub trait Document: Serialize + DeserializeOwned {
     fn id(&self) -> &str;
@@ -13,8 +27,85 @@ pub trait Document: Serialize + DeserializeOwned {
     fn set_rev(&mut self, rev: impl ToString) -> &mut Self;
 }
 
+/// An untyped document, for when the shape of the stored JSON is not known in advance.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct JsonDocument {
+    inner: Map<String, Value>,
+}
+
+impl JsonDocument {
+    pub fn new(id: impl ToString) -> Self {
+        let mut doc = Self::default();
+        doc.set_id(id);
+        doc
+    }
+
+    pub fn into_inner(self) -> Map<String, Value> {
+        self.inner
+    }
+}
+
+impl Document for JsonDocument {
+    fn id(&self) -> &str {
+        self.inner
+            .get("_id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+    }
+
+    fn rev(&self) -> Option<&str> {
+        self.inner.get("_rev").and_then(Value::as_str)
+    }
+
+    fn set_id(&mut self, id: impl ToString) -> &mut Self {
+        self.inner.insert("_id".to_string(), id.to_string().into());
+        self
+    }
+
+    fn set_rev(&mut self, rev: impl ToString) -> &mut Self {
+        self.inner
+            .insert("_rev".to_string(), rev.to_string().into());
+        self
+    }
+}
+
+impl Deref for JsonDocument {
+    type Target = Map<String, Value>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for JsonDocument {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl From<Map<String, Value>> for JsonDocument {
+    fn from(inner: Map<String, Value>) -> Self {
+        Self { inner }
+    }
+}
+
+impl From<JsonDocument> for Value {
+    fn from(doc: JsonDocument) -> Self {
+        Value::Object(doc.inner)
+    }
+}
+
+impl TryFrom<Value> for JsonDocument {
+    type Error = serde_json::Error;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        serde_json::from_value(value)
+    }
+}
+
 pub struct Documents<'db> {
-    client: &'db FutonClient,
+    client: &'db Transport,
     url: &'db Url,
     db_name: &'db str,
     credentials: &'db Credentials,
@@ -22,7 +113,7 @@ pub struct Documents<'db> {
 
 impl<'db> Documents<'db> {
     pub fn new(
-        client: &'db FutonClient,
+        client: &'db Transport,
         url: &'db Url,
         db_name: &'db str,
         credentials: &'db Credentials,
@@ -37,8 +128,19 @@ impl<'db> Documents<'db> {
 }
 
 impl<'db> Documents<'db> {
+    #[inline]
+    pub async fn create<D: Document + Debug>(&self, doc: D) -> FutonResult<D> {
+        self.create_with(doc, WriteOptions::default()).await
+    }
+
+    /// Creates a document. With [`WriteOptions::batch`] the server acknowledges the
+    /// write before committing it, so the returned document has no rev set.
     #[tracing::instrument(skip(self))]
-    pub async fn create<D: Document + Debug>(&self, mut doc: D) -> FutonResult<D> {
+    pub async fn create_with<D: Document + Debug>(
+        &self,
+        mut doc: D,
+        options: WriteOptions,
+    ) -> FutonResult<D> {
         debug_assert!(doc.rev().is_none(), "doc should not have a rev set when creating. Use Documents::create_or_update() instead");
         let mut client = self.client.clone();
 
@@ -46,32 +148,52 @@ impl<'db> Documents<'db> {
             .method(Method::POST)?
             .credentials(self.credentials.clone())
             .database(self.db_name)
+            .query_params(options.to_query())
             .json(&doc)?;
 
         let res = client.call(req).await?;
 
-        let DocumentOperation { rev, .. } = res.error_for_status()?.into_body().json()?;
+        let DocumentOperation { id, rev, .. } = res.error_for_status()?.into_body().json()?;
 
-        doc.set_rev(rev);
+        doc.set_id(id);
+        if let Some(rev) = rev {
+            doc.set_rev(rev);
+        }
         Ok(doc)
     }
 
+    #[inline]
+    pub async fn create_or_update<D: Document + Debug>(&self, doc: D) -> FutonResult<D> {
+        self.create_or_update_with(doc, WriteOptions::default())
+            .await
+    }
+
+    /// Creates or updates a document. With [`WriteOptions::new_edits`] disabled the
+    /// document is stored with its own rev, as when restoring a backup.
     #[tracing::instrument(skip(self))]
-    pub async fn create_or_update<D: Document + Debug>(&self, mut doc: D) -> FutonResult<D> {
+    pub async fn create_or_update_with<D: Document + Debug>(
+        &self,
+        mut doc: D,
+        options: WriteOptions,
+    ) -> FutonResult<D> {
         let mut client = self.client.clone();
 
+        let rev = doc.rev().filter(|_| options.new_edits);
         let req = FutonRequest::new(self.url.clone())?
             .method(Method::PUT)?
             .credentials(self.credentials.clone())
             .database(self.db_name)
-            .document(doc.id(), doc.rev())
+            .document(doc.id(), rev)
+            .query_params(options.to_query())
             .json(&doc)?;
 
         let res = client.call(req).await?;
 
         let DocumentOperation { rev, .. } = res.error_for_status()?.into_body().json()?;
 
-        doc.set_rev(rev);
+        if let Some(rev) = rev {
+            doc.set_rev(rev);
+        }
         Ok(doc)
     }
 
@@ -105,8 +227,131 @@ impl<'db> Documents<'db> {
         self.fetch(id, rev).await
     }
 
+    /// Fetches a document only if its current rev differs from `rev`.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_if_none_match<D: Document>(
+        &self,
+        id: &str,
+        rev: &str,
+    ) -> FutonResult<Option<Conditional<D>>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::GET)?
+            .credentials(self.credentials.clone())
+            .header(http::header::IF_NONE_MATCH, format!("\"{rev}\""))?
+            .database(self.db_name)
+            .document(id, None);
+
+        let res = client.call(req).await?;
+
+        if res.is_not_found() {
+            return Ok(None);
+        }
+
+        if res.is_not_modified() {
+            return Ok(Some(Conditional::NotModified));
+        }
+
+        let doc = res.error_for_status()?.into_body().json()?;
+        Ok(Some(Conditional::Modified(doc)))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_with<D: Document>(
+        &self,
+        id: &str,
+        options: GetOptions,
+    ) -> FutonResult<Option<FetchedDocument<D>>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::GET)?
+            .credentials(self.credentials.clone())
+            .database(self.db_name)
+            .document(id, None)
+            .query_string(&options)?;
+
+        let res = client.call(req).await?;
+
+        if res.is_not_found() {
+            return Ok(None);
+        }
+
+        let doc = res.error_for_status()?.into_body().json()?;
+        Ok(Some(doc))
+    }
+
+    /// Fetches the last revision of a deleted document before it was deleted.
+    ///
+    /// Returns `None` if the document does not exist, is not deleted, or if the
+    /// revision body is no longer available after a compaction.
+    #[tracing::instrument(skip(self))]
+    pub async fn get_deleted<D: Document>(
+        &self,
+        id: &str,
+    ) -> FutonResult<Option<FetchedDocument<D>>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::GET)?
+            .credentials(self.credentials.clone())
+            .header(http::header::ACCEPT, "application/json")?
+            .database(self.db_name)
+            .document(id, None)
+            .query_param("open_revs", &OpenRevs::All.to_query()?)
+            .query_param("revs", "true");
+
+        let res = client.call(req).await?;
+
+        if res.is_not_found() {
+            return Ok(None);
+        }
+
+        let leaves: Vec<OpenRev<FetchedDocument<JsonDocument>>> =
+            res.error_for_status()?.into_body().json()?;
+        let leaves = leaves
+            .into_iter()
+            .filter_map(OpenRev::ok)
+            .collect::<Vec<_>>();
+
+        let is_deleted = |leaf: &FetchedDocument<JsonDocument>| {
+            leaf.doc.get("_deleted") == Some(&Value::Bool(true))
+        };
+        if leaves.is_empty() || !leaves.iter().all(is_deleted) {
+            return Ok(None);
+        }
+
+        let previous = leaves
+            .into_iter()
+            .filter_map(|leaf| leaf.revisions)
+            .max_by(|a, b| (a.start, &a.ids).cmp(&(b.start, &b.ids)))
+            .and_then(|revisions| revisions.revs().into_iter().nth(1));
+
+        match previous {
+            Some(rev) => {
+                let options = GetOptions {
+                    revs: true,
+                    ..GetOptions::rev(rev)
+                };
+                self.get_with(id, options).await
+            }
+            None => Ok(None),
+        }
+    }
+
     #[inline]
     async fn fetch<D: Document>(&self, id: &str, rev: Option<&str>) -> FutonResult<Option<D>> {
+        match self.fetch_existing(id, rev).await {
+            Ok(doc) => Ok(Some(doc)),
+            Err(err) if err.is_not_found() => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Like `fetch`, failing with the error CouchDB returned when the document
+    /// doesn't exist.
+    async fn fetch_existing<D: Document>(&self, id: &str, rev: Option<&str>) -> FutonResult<D> {
         let mut client = self.client.clone();
 
         let req = FutonRequest::new(self.url.clone())?
@@ -117,6 +362,69 @@ impl<'db> Documents<'db> {
 
         let res = client.call(req).await?;
 
+        let doc = res.error_for_status()?.into_body().json()?;
+        Ok(doc)
+    }
+
+    /// Fetches a document, applies `update` and stores it, retrying with the
+    /// default [`RetryPolicy`] when another writer updated it in the meantime.
+    #[inline]
+    pub async fn update_with<D, F>(&self, id: &str, update: F) -> FutonResult<Updated<D>>
+    where
+        D: Document + Debug,
+        F: FnMut(&mut D),
+    {
+        self.update_with_policy(id, RetryPolicy::default(), update)
+            .await
+    }
+
+    #[tracing::instrument(skip(self, update))]
+    pub async fn update_with_policy<D, F>(
+        &self,
+        id: &str,
+        policy: RetryPolicy,
+        mut update: F,
+    ) -> FutonResult<Updated<D>>
+    where
+        D: Document + Debug,
+        F: FnMut(&mut D),
+    {
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+
+            let mut doc = self.fetch_existing::<D>(id, None).await?;
+
+            update(&mut doc);
+
+            match self.create_or_update(doc).await {
+                Ok(doc) => return Ok(Updated { doc, attempts }),
+                Err(err) if err.is_conflict() && attempts < policy.max_attempts => {
+                    let backoff = policy.backoff(attempts);
+                    tracing::debug!(attempts, ?backoff, "document update conflict, retrying");
+                    tokio::time::sleep(backoff).await;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_with_conflicts<D: Document>(
+        &self,
+        id: &str,
+    ) -> FutonResult<Option<WithConflicts<D>>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::GET)?
+            .credentials(self.credentials.clone())
+            .database(self.db_name)
+            .document(id, None)
+            .query_param("conflicts", "true");
+
+        let res = client.call(req).await?;
+
         if res.is_not_found() {
             return Ok(None);
         }
@@ -125,6 +433,111 @@ impl<'db> Documents<'db> {
         Ok(Some(doc))
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn open_revs<D: Document>(
+        &self,
+        id: &str,
+        revs: OpenRevs,
+    ) -> FutonResult<Vec<OpenRev<D>>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::GET)?
+            .credentials(self.credentials.clone())
+            .header(http::header::ACCEPT, "application/json")?
+            .database(self.db_name)
+            .document(id, None)
+            .query_param("open_revs", &revs.to_query()?);
+
+        let res = client.call(req).await?;
+
+        if res.is_not_found() {
+            return Ok(Vec::new());
+        }
+
+        let revs = res.error_for_status()?.into_body().json()?;
+        Ok(revs)
+    }
+
+    /// Resolves the conflicts of a document in a single `_bulk_docs` request.
+    ///
+    /// `merge` receives the winning revision and the conflicting ones, and returns
+    /// the document to store on top of the winning revision. All conflicting
+    /// revisions are deleted.
+    #[tracing::instrument(skip(self, merge))]
+    pub async fn resolve_conflicts<D, F>(&self, id: &str, merge: F) -> FutonResult<Option<D>>
+    where
+        D: Document + Debug,
+        F: FnOnce(D, Vec<D>) -> D,
+    {
+        let WithConflicts { doc, conflicts } = match self.get_with_conflicts::<D>(id).await? {
+            Some(doc) => doc,
+            None => return Ok(None),
+        };
+
+        if conflicts.is_empty() {
+            return Ok(Some(doc));
+        }
+
+        let winning_rev = doc.rev().map(ToString::to_string);
+        let losers = self
+            .open_revs::<D>(id, OpenRevs::Revs(conflicts.clone()))
+            .await?
+            .into_iter()
+            .filter_map(OpenRev::ok)
+            .collect();
+
+        let mut merged = merge(doc, losers);
+        merged.set_id(id);
+        if let Some(rev) = winning_rev {
+            merged.set_rev(rev);
+        }
+
+        let mut docs = vec![serde_json::to_value(&merged)?];
+        for rev in conflicts {
+            docs.push(serde_json::to_value(Tombstone::new(id, rev))?);
+        }
+
+        let mut results = self
+            .bulk_docs(&docs, BulkDocsParams::default())
+            .await?
+            .into_iter();
+
+        if let Some(result) = results.next() {
+            merged.set_rev(result.into_result()?.rev);
+        }
+
+        for result in results {
+            result.into_result()?;
+        }
+
+        Ok(Some(merged))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn bulk_docs<D: Serialize + Debug>(
+        &self,
+        docs: &[D],
+        params: BulkDocsParams,
+    ) -> FutonResult<Vec<BulkDocResult>> {
+        let mut client = self.client.clone();
+
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .database(self.db_name)
+            .segment("_bulk_docs")
+            .json(serde_json::json!({
+                "docs": docs,
+                "new_edits": params.new_edits,
+            }))?;
+
+        let res = client.call(req).await?;
+
+        let results = res.error_for_status()?.into_body().json()?;
+        Ok(results)
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn delete<D: Document + Debug>(&self, doc: D) -> FutonResult<D> {
         let mut doc = match doc.rev() {
@@ -148,7 +561,9 @@ impl<'db> Documents<'db> {
 
         let DocumentOperation { rev, .. } = res.error_for_status()?.into_body().json()?;
 
-        doc.set_rev(rev);
+        if let Some(rev) = rev {
+            doc.set_rev(rev);
+        }
         Ok(doc)
     }
 
@@ -177,7 +592,10 @@ impl<'db> Documents<'db> {
 
         let DocumentOperation { id, rev, .. } = res.error_for_status()?.into_body().json()?;
 
-        doc.set_id(id).set_rev(rev);
+        doc.set_id(id);
+        if let Some(rev) = rev {
+            doc.set_rev(rev);
+        }
         Ok(doc)
     }
 }
@@ -29,6 +29,26 @@ pub enum FutonError {
     UnknownBadRequest(ErrorResponse),
     #[error("{0}")]
     Conflict(ErrorResponse),
+    #[error("{0}")]
+    Forbidden(ErrorResponse),
+    #[error("{0}")]
+    PreconditionFailed(ErrorResponse),
+    #[error("{0}")]
+    PayloadTooLarge(ErrorResponse),
+    #[error("{0}")]
+    UnsupportedMediaType(ErrorResponse),
+    #[error("{0}")]
+    ExpectationFailed(ErrorResponse),
+    #[error("{0}")]
+    TooManyRequests(ErrorResponse),
+    #[error("{0}")]
+    InternalServerError(ErrorResponse),
+    #[error("{0}")]
+    ServiceUnavailable(ErrorResponse),
+    #[error("{0}")]
+    Timeout(ErrorResponse),
+    #[error("{0}")]
+    BulkDoc(#[from] BulkDocError),
     #[error("invalid database name: '{0}'. See: https://docs.couchdb.org/en/stable/api/database/common.html#put--db")]
     InvalidDatabaseName(String),
 }
@@ -37,6 +57,70 @@ impl FutonError {
     pub fn is_not_found(&self) -> bool {
         matches!(self, Self::NotFound(_))
     }
+
+    pub fn is_conflict(&self) -> bool {
+        match self {
+            Self::Conflict(_) => true,
+            Self::BulkDoc(err) => err.is_conflict(),
+            _ => false,
+        }
+    }
+
+    /// Whether the same request may succeed if sent again later.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::TooManyRequests(_) | Self::ServiceUnavailable(_) | Self::Timeout(_) => true,
+            // `504 Gateway Timeout` is mapped to `Timeout`.
+            Self::UnknownError(err) => err.status == StatusCode::BAD_GATEWAY,
+            Self::Client(err) => err.is_retryable(),
+            _ => false,
+        }
+    }
+
+    /// The error returned by CouchDB, if the request reached it.
+    pub fn error_response(&self) -> Option<&ErrorResponse> {
+        match self {
+            Self::NotFound(err)
+            | Self::Unauthorized(err)
+            | Self::UnknownError(err)
+            | Self::InvalidRevFormat(err)
+            | Self::UnknownBadRequest(err)
+            | Self::Conflict(err)
+            | Self::Forbidden(err)
+            | Self::PreconditionFailed(err)
+            | Self::PayloadTooLarge(err)
+            | Self::UnsupportedMediaType(err)
+            | Self::ExpectationFailed(err)
+            | Self::TooManyRequests(err)
+            | Self::InternalServerError(err)
+            | Self::ServiceUnavailable(err)
+            | Self::Timeout(err)
+            | Self::Client(futon_core::Error::CouchError(err)) => Some(err),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self) -> Option<StatusCode> {
+        self.error_response().map(|err| err.status)
+    }
+}
+
+/// A document rejected by a bulk write, the request itself having succeeded.
+///
+/// CouchDB reports these without a status code, only with its `error` and
+/// `reason`.
+#[derive(Debug, Clone, Error)]
+#[error("document '{id}' rejected: {error}: {reason}")]
+pub struct BulkDocError {
+    pub id: String,
+    pub error: String,
+    pub reason: String,
+}
+
+impl BulkDocError {
+    pub fn is_conflict(&self) -> bool {
+        self.error == "conflict"
+    }
 }
 
 impl From<ErrorResponse> for FutonError {
@@ -49,6 +133,18 @@ impl From<ErrorResponse> for FutonError {
                 "invalid rev format" => FutonError::InvalidRevFormat(error),
                 _ => FutonError::UnknownBadRequest(error),
             },
+            StatusCode::FORBIDDEN => FutonError::Forbidden(error),
+            StatusCode::PRECONDITION_FAILED => FutonError::PreconditionFailed(error),
+            StatusCode::PAYLOAD_TOO_LARGE => FutonError::PayloadTooLarge(error),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => FutonError::UnsupportedMediaType(error),
+            StatusCode::EXPECTATION_FAILED => FutonError::ExpectationFailed(error),
+            StatusCode::TOO_MANY_REQUESTS => FutonError::TooManyRequests(error),
+            StatusCode::REQUEST_TIMEOUT | StatusCode::GATEWAY_TIMEOUT => FutonError::Timeout(error),
+            StatusCode::SERVICE_UNAVAILABLE => FutonError::ServiceUnavailable(error),
+            StatusCode::INTERNAL_SERVER_ERROR => match error.error.as_str() {
+                "timeout" => FutonError::Timeout(error),
+                _ => FutonError::InternalServerError(error),
+            },
             _ => FutonError::UnknownError(error),
         }
     }
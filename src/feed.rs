@@ -0,0 +1,68 @@
+use futon_core::FutonBodyStream;
+use futures::{Stream, StreamExt, TryStreamExt};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
+
+use crate::FutonResult;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum FeedLine<T> {
+    Event(T),
+    End {
+        #[allow(dead_code)]
+        last_seq: Value,
+    },
+}
+
+struct Lines {
+    stream: FutonBodyStream,
+    buffer: Vec<u8>,
+    done: bool,
+}
+
+/// Splits a continuous feed into its newline-delimited JSON events,
+/// skipping heartbeats and the trailing `last_seq` line.
+pub(crate) fn json_lines<T>(stream: FutonBodyStream) -> impl Stream<Item = FutonResult<T>>
+where
+    T: DeserializeOwned,
+{
+    let lines = Lines {
+        stream,
+        buffer: Vec::new(),
+        done: false,
+    };
+
+    futures::stream::try_unfold(lines, |mut lines| async move {
+        loop {
+            if let Some(pos) = lines.buffer.iter().position(|b| *b == b'\n') {
+                let line = lines.buffer.drain(..=pos).collect::<Vec<_>>();
+                return Ok(Some((line, lines)));
+            }
+
+            if lines.done {
+                if lines.buffer.is_empty() {
+                    return Ok(None);
+                }
+
+                let line = std::mem::take(&mut lines.buffer);
+                return Ok(Some((line, lines)));
+            }
+
+            match lines.stream.next().await {
+                Some(chunk) => lines.buffer.extend_from_slice(&chunk?),
+                None => lines.done = true,
+            }
+        }
+    })
+    .try_filter_map(|line| async move {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+
+        match serde_json::from_slice(&line)? {
+            FeedLine::Event(event) => Ok(Some(event)),
+            FeedLine::End { .. } => Ok(None),
+        }
+    })
+}
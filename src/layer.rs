@@ -0,0 +1,278 @@
+//! Tower layers for the transport requests are sent through.
+//!
+//! Layers are added on top of the current transport with [`Futon::layer`],
+//! the last one added being the first to see a request:
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use futon::{
+//!     layer::{ConcurrencyLimitLayer, RetryLayer, TimeoutLayer},
+//!     request::RetryPolicy,
+//!     Futon,
+//! };
+//! use url::Url;
+//!
+//! let futon = Futon::new(Url::parse("http://localhost:5984").unwrap())
+//!     .layer(ConcurrencyLimitLayer::new(32))
+//!     .layer(TimeoutLayer::new(Duration::from_secs(10)))
+//!     .layer(RetryLayer::new(RetryPolicy::default()));
+//! ```
+//!
+//! [`Futon::layer`]: crate::Futon::layer
+
+use std::{
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futon_core::{Error, FutonRequest, FutonResponse, Layer, Service};
+use futures::future::BoxFuture;
+use http::StatusCode;
+use tokio::time::Instant;
+use tower::{retry, ServiceExt};
+
+use crate::request::RetryPolicy;
+
+#[cfg(any(feature = "metrics", feature = "opentelemetry"))]
+mod endpoint;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "opentelemetry")]
+mod trace;
+
+#[cfg(feature = "metrics")]
+pub use self::metrics::{Metrics, MetricsLayer};
+#[cfg(feature = "opentelemetry")]
+pub use self::trace::{TraceContext, TraceContextLayer};
+pub use tower::limit::{ConcurrencyLimit, ConcurrencyLimitLayer};
+
+/// Retries idempotent requests failing with a connection error, a timeout,
+/// `429 Too Many Requests` or `503 Service Unavailable`, with exponential
+/// backoff and jitter.
+#[derive(Debug, Clone, Default)]
+pub struct RetryLayer {
+    policy: RetryPolicy,
+}
+
+impl RetryLayer {
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = retry::Retry<Retries, S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        let retries = Retries {
+            policy: self.policy.clone(),
+            attempts: 1,
+        };
+        retry::Retry::new(retries, inner)
+    }
+}
+
+/// The [`retry::Policy`] applied by [`RetryLayer`].
+#[derive(Debug, Clone)]
+pub struct Retries {
+    policy: RetryPolicy,
+    attempts: usize,
+}
+
+impl retry::Policy<FutonRequest, FutonResponse, Error> for Retries {
+    type Future = BoxFuture<'static, Self>;
+
+    fn retry(
+        &self,
+        req: &FutonRequest,
+        result: Result<&FutonResponse, &Error>,
+    ) -> Option<Self::Future> {
+        if self.attempts >= self.policy.max_attempts || !req.http_method().is_idempotent() {
+            return None;
+        }
+
+        let retryable = match result {
+            Ok(res) => matches!(
+                res.status(),
+                StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+            ),
+            Err(err) => err.is_retryable(),
+        };
+        if !retryable {
+            return None;
+        }
+
+        let backoff = jitter(self.policy.backoff(self.attempts));
+        tracing::debug!(attempts = self.attempts, ?backoff, url = %req.url(), "retrying request");
+        let next = Self {
+            policy: self.policy.clone(),
+            attempts: self.attempts + 1,
+        };
+        Some(Box::pin(async move {
+            tokio::time::sleep(backoff).await;
+            next
+        }))
+    }
+
+    fn clone_request(&self, req: &FutonRequest) -> Option<FutonRequest> {
+        Some(req.clone())
+    }
+}
+
+/// Spreads retries over the upper half of the backoff, so that clients
+/// failing at the same time don't retry in lockstep.
+fn jitter(backoff: Duration) -> Duration {
+    let half = backoff / 2;
+    half + half.mul_f64(fastrand::f64())
+}
+
+/// Fails requests that haven't received a response within the timeout with
+/// [`Error::Timeout`].
+#[derive(Debug, Clone)]
+pub struct TimeoutLayer {
+    timeout: Duration,
+}
+
+impl TimeoutLayer {
+    pub fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+impl<S> Layer<S> for TimeoutLayer {
+    type Service = Timeout<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Timeout {
+            inner,
+            timeout: self.timeout,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Timeout<S> {
+    inner: S,
+    timeout: Duration,
+}
+
+impl<S> Service<FutonRequest> for Timeout<S>
+where
+    S: Service<FutonRequest, Response = FutonResponse, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = BoxFuture<'static, Result<FutonResponse, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        let timeout = self.timeout;
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            tokio::time::timeout(timeout, fut)
+                .await
+                .map_err(|_| Error::Timeout(timeout))?
+        })
+    }
+}
+
+/// Sends at most `num` requests every `per`, delaying the ones over the limit.
+///
+/// The limit is shared by every clone of the service.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    num: u64,
+    per: Duration,
+}
+
+impl RateLimitLayer {
+    pub fn new(num: u64, per: Duration) -> Self {
+        assert!(num > 0, "rate limit must allow at least one request");
+        Self { num, per }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            num: self.num,
+            per: self.per,
+            window: Arc::new(Mutex::new(Window {
+                start: Instant::now(),
+                remaining: self.num,
+            })),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    num: u64,
+    per: Duration,
+    window: Arc<Mutex<Window>>,
+}
+
+#[derive(Debug)]
+struct Window {
+    start: Instant,
+    remaining: u64,
+}
+
+impl<S> RateLimit<S> {
+    /// Takes a slot in the current window, or the first window with one left,
+    /// returning how long to wait for it.
+    fn reserve(&self) -> Duration {
+        let mut window = self.window.lock().unwrap_or_else(|err| err.into_inner());
+        let now = Instant::now();
+        if now >= window.start + self.per {
+            window.start = now;
+            window.remaining = self.num;
+        }
+        if window.remaining == 0 {
+            window.start += self.per;
+            window.remaining = self.num;
+        }
+        window.remaining -= 1;
+        window.start.saturating_duration_since(now)
+    }
+}
+
+impl<S> Service<FutonRequest> for RateLimit<S>
+where
+    S: Service<FutonRequest, Response = FutonResponse, Error = Error> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = BoxFuture<'static, Result<FutonResponse, Error>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        let delay = self.reserve();
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            if !delay.is_zero() {
+                tracing::debug!(?delay, "rate limited");
+                tokio::time::sleep(delay).await;
+            }
+            inner.oneshot(req).await
+        })
+    }
+}
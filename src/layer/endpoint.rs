@@ -0,0 +1,67 @@
+use futon_core::FutonRequest;
+
+/// The kind of CouchDB endpoint a request targets, as a low cardinality label
+/// for metrics and traces.
+pub(crate) fn endpoint(req: &FutonRequest) -> &'static str {
+    match req.database_path() {
+        Some(path) => database_endpoint(&path.collect::<Vec<_>>()),
+        None => server_endpoint(req),
+    }
+}
+
+fn server_endpoint(req: &FutonRequest) -> &'static str {
+    let first = req
+        .url()
+        .path_segments()
+        .and_then(|mut segments| segments.find(|segment| segment.starts_with('_')));
+    match first {
+        None => "server",
+        Some("_up") => "up",
+        Some("_all_dbs") => "all_dbs",
+        Some("_dbs_info") => "dbs_info",
+        Some("_membership") => "membership",
+        Some("_cluster_setup") => "cluster_setup",
+        Some("_db_updates") => "db_updates",
+        Some("_node") => "node",
+        Some("_active_tasks") => "active_tasks",
+        Some("_replicate") => "replicate",
+        Some("_scheduler") => "scheduler",
+        Some("_session") => "session",
+        Some("_uuids") => "uuids",
+        Some(_) => "other",
+    }
+}
+
+fn database_endpoint(path: &[&str]) -> &'static str {
+    match path {
+        [] => "database",
+        ["_partition", _] => "partition",
+        ["_partition", _, rest @ ..] => database_endpoint(rest),
+        ["_design", _, "_view", ..] => "view",
+        ["_design", _, "_info"] => "design_info",
+        ["_design", _, "_update", ..] => "update",
+        ["_design", _, _, ..] => "design_attachment",
+        ["_design", ..] => "design_document",
+        ["_local", ..] => "local_document",
+        ["_all_docs", ..] => "all_docs",
+        ["_design_docs", ..] => "design_docs",
+        ["_local_docs", ..] => "local_docs",
+        ["_find"] => "find",
+        ["_explain"] => "explain",
+        ["_index", ..] => "index",
+        ["_changes"] => "changes",
+        ["_bulk_docs"] => "bulk_docs",
+        ["_bulk_get"] => "bulk_get",
+        ["_compact", ..] => "compact",
+        ["_view_cleanup"] => "view_cleanup",
+        ["_purge"] => "purge",
+        ["_purged_infos_limit"] => "purged_infos_limit",
+        ["_revs_diff"] => "revs_diff",
+        ["_missing_revs"] => "missing_revs",
+        ["_revs_limit"] => "revs_limit",
+        ["_security"] => "security",
+        [special, ..] if special.starts_with('_') => "other",
+        [_] => "document",
+        [_, ..] => "attachment",
+    }
+}
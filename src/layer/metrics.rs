@@ -0,0 +1,88 @@
+use std::{
+    task::{Context, Poll},
+    time::Instant,
+};
+
+use futon_core::{Error, FutonRequest, FutonResponse, Layer, Service};
+use futures::future::BoxFuture;
+use metrics::Label;
+
+use super::endpoint::endpoint;
+
+/// Records every request with the [`metrics`] facade:
+///
+/// - `futon_requests_total`, a counter of completed requests
+/// - `futon_request_duration_seconds`, a histogram of request latencies
+/// - `futon_request_errors_total`, a counter of transport errors and error
+///   statuses
+///
+/// Each is labelled by `method`, `endpoint` (such as `document`, `view`,
+/// `find` or `changes`), `db` (empty for server endpoints) and `status`
+/// (`error` when no response was received).
+#[derive(Debug, Clone, Default)]
+pub struct MetricsLayer;
+
+impl MetricsLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for MetricsLayer {
+    type Service = Metrics<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Metrics { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Metrics<S> {
+    inner: S,
+}
+
+impl<S> Service<FutonRequest> for Metrics<S>
+where
+    S: Service<FutonRequest, Response = FutonResponse, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = BoxFuture<'static, Result<FutonResponse, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        let labels = vec![
+            Label::new("method", req.http_method().to_string()),
+            Label::new("endpoint", endpoint(&req)),
+            Label::new("db", req.database_name().unwrap_or_default().to_string()),
+        ];
+        let start = Instant::now();
+        let fut = self.inner.call(req);
+        Box::pin(async move {
+            let res = fut.await;
+            let (status, failed) = match &res {
+                Ok(res) => (
+                    res.status().as_u16().to_string(),
+                    res.status().is_client_error() || res.status().is_server_error(),
+                ),
+                Err(_) => ("error".to_string(), true),
+            };
+
+            let mut labels = labels;
+            labels.push(Label::new("status", status));
+            metrics::counter!("futon_requests_total", labels.clone()).increment(1);
+            metrics::histogram!("futon_request_duration_seconds", labels.clone())
+                .record(start.elapsed().as_secs_f64());
+            if failed {
+                metrics::counter!("futon_request_errors_total", labels).increment(1);
+            }
+            res
+        })
+    }
+}
@@ -0,0 +1,121 @@
+use std::task::{Context, Poll};
+
+use futon_core::{Error, FutonRequest, FutonResponse, Layer, Service};
+use futures::future::BoxFuture;
+use opentelemetry::{global, propagation::Injector};
+use tracing::{field::Empty, Instrument, Span};
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+use super::endpoint::endpoint;
+
+/// Sends every request in a `couchdb` client span with the `db.system`,
+/// `db.name` and `db.operation` semantic convention attributes, and
+/// propagates it through the headers of the global text map propagator, such
+/// as the W3C `traceparent` and `tracestate` headers once
+/// `opentelemetry::global::set_text_map_propagator` has been given a
+/// `TraceContextPropagator`.
+///
+/// Headers are only injected when the subscriber includes a
+/// `tracing_opentelemetry` layer.
+#[derive(Debug, Clone, Default)]
+pub struct TraceContextLayer;
+
+impl TraceContextLayer {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl<S> Layer<S> for TraceContextLayer {
+    type Service = TraceContext<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        TraceContext { inner }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceContext<S> {
+    inner: S,
+}
+
+impl<S> Service<FutonRequest> for TraceContext<S>
+where
+    S: Service<FutonRequest, Response = FutonResponse, Error = Error>,
+    S::Future: Send + 'static,
+{
+    type Response = FutonResponse;
+
+    type Error = Error;
+
+    type Future = BoxFuture<'static, Result<FutonResponse, Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: FutonRequest) -> Self::Future {
+        let method = req.http_method().clone();
+        let operation = endpoint(&req);
+        let span = tracing::info_span!(
+            "couchdb",
+            otel.name = %format_args!("{method} {operation}"),
+            otel.kind = "client",
+            otel.status_code = Empty,
+            db.system = "couchdb",
+            db.name = req.database_name(),
+            db.operation = operation,
+            http.request.method = %method,
+            http.response.status_code = Empty,
+        );
+
+        let req = match inject(&span, req) {
+            Ok(req) => req,
+            Err(err) => return Box::pin(futures::future::ready(Err(err))),
+        };
+        let fut = span.in_scope(|| self.inner.call(req));
+        Box::pin(
+            async move {
+                let res = fut.await;
+                let span = Span::current();
+                match &res {
+                    Ok(res) => {
+                        span.record(
+                            "http.response.status_code",
+                            i64::from(res.status().as_u16()),
+                        );
+                        if res.status().is_server_error() {
+                            span.record("otel.status_code", "ERROR");
+                        }
+                    }
+                    Err(_) => {
+                        span.record("otel.status_code", "ERROR");
+                    }
+                }
+                res
+            }
+            .instrument(span),
+        )
+    }
+}
+
+fn inject(span: &Span, req: FutonRequest) -> Result<FutonRequest, Error> {
+    let cx = span.context();
+    let mut headers = Headers::default();
+    global::get_text_map_propagator(|propagator| propagator.inject_context(&cx, &mut headers));
+
+    headers
+        .0
+        .into_iter()
+        .try_fold(req, |req, (name, value)| Ok(req.header(name, value)?))
+}
+
+/// Collects the propagated headers, as requests are built by value.
+#[derive(Default)]
+struct Headers(Vec<(String, String)>);
+
+impl Injector for Headers {
+    fn set(&mut self, key: &str, value: String) {
+        self.0.push((key.to_string(), value));
+    }
+}
@@ -1,9 +1,15 @@
-pub use futon_core::Credentials;
+#[cfg(all(unix, feature = "hyper"))]
+pub use futon_core::UnixConnector;
+#[cfg(feature = "hyper")]
+pub use futon_core::{Connect, FutonClient, FutonClientBuilder, Proxy};
+pub use futon_core::{Credentials, FutonRequest, FutonResponse, ResponseCache, Transport};
+#[cfg(feature = "derive")]
+pub use futon_derive::document;
 
 use db::Database;
 use error::FutonError;
 
-use futon_core::FutonClient;
+use futon_core::{BoxError, Layer, Service};
 use meta::Meta;
 
 use url::Url;
@@ -12,20 +18,63 @@ pub mod db;
 pub mod ddoc;
 pub mod document;
 pub mod error;
+mod feed;
+pub mod layer;
+pub mod local;
 pub mod meta;
 pub mod request;
 pub mod response;
 
+#[doc(hidden)]
+pub mod __private {
+    pub use serde_json;
+}
+
 pub type FutonResult<T> = std::result::Result<T, FutonError>;
 
 pub struct Futon {
-    client: FutonClient,
+    client: Transport,
     url: Url,
     credentials: Credentials,
 }
 
+#[cfg(feature = "hyper")]
 impl Futon {
     pub fn new<U: Into<Url>>(url: U) -> Self {
+        Self::new_with_client(url, FutonClient::default())
+    }
+
+    /// Talks to CouchDB through a client configured with
+    /// [`FutonClient::builder`], taking credentials from the URL like
+    /// [`Futon::new`].
+    pub fn new_with_client<U, C>(url: U, client: FutonClient<C>) -> Self
+    where
+        U: Into<Url>,
+        C: Connect + Clone + Send + Sync + 'static,
+    {
+        Self::new_with_transport(url, client)
+    }
+
+    pub fn new_with_credentials<U: Into<Url>>(url: U, credentials: Credentials) -> Self {
+        Self::from_parts(
+            Transport::new(FutonClient::default()),
+            url.into(),
+            credentials,
+        )
+    }
+}
+
+impl Futon {
+    /// Sends requests through any service able to handle a [`FutonRequest`],
+    /// such as another HTTP client, a mock, or a `FutonClient` wrapped in
+    /// tower layers. Credentials are taken from the URL like `Futon::new`.
+    pub fn new_with_transport<U, S>(url: U, service: S) -> Self
+    where
+        U: Into<Url>,
+        S: Service<FutonRequest, Response = FutonResponse> + Clone + Send + 'static,
+        S::Error: Into<BoxError>,
+        S::Future: Send + 'static,
+    {
         let url = url.into();
         let username = url.username();
         let credentials = if username.is_empty() {
@@ -35,15 +84,14 @@ impl Futon {
         }
         .map(|(u, p)| Credentials::basic(u, p))
         .unwrap_or_default();
-        Self::new_with_credentials(url, credentials)
+        Self::from_parts(Transport::new(service), url, credentials)
     }
 
-    pub fn new_with_credentials<U: Into<Url>>(url: U, credentials: Credentials) -> Self {
-        let mut url = url.into();
+    fn from_parts(client: Transport, mut url: Url, credentials: Credentials) -> Self {
         url.set_username("").unwrap();
         url.set_password(None).unwrap();
         Self {
-            client: FutonClient::default(),
+            client,
             url,
             credentials,
         }
@@ -51,6 +99,24 @@ impl Futon {
 }
 
 impl Futon {
+    /// Caches `GET` responses in memory, revalidating them through their ETag.
+    pub fn with_response_cache(self, cache: ResponseCache) -> Self {
+        self.layer(cache)
+    }
+
+    /// Wraps the transport requests are sent through in a tower layer, such
+    /// as the ones in [`layer`].
+    pub fn layer<L>(mut self, layer: L) -> Self
+    where
+        L: Layer<Transport>,
+        L::Service: Service<FutonRequest, Response = FutonResponse> + Clone + Send + 'static,
+        <L::Service as Service<FutonRequest>>::Error: Into<BoxError>,
+        <L::Service as Service<FutonRequest>>::Future: Send + 'static,
+    {
+        self.client = Transport::new(layer.layer(self.client));
+        self
+    }
+
     pub fn meta(&self) -> Meta {
         Meta::new(
             self.client.clone(),
@@ -0,0 +1,117 @@
+use futon_core::{Credentials, FutonRequest, Service, Transport};
+use http::Method;
+use std::fmt::Debug;
+use url::Url;
+
+use crate::{
+    ddoc::DesignDocuments,
+    document::Document,
+    request::ViewParams,
+    response::{DocumentOperation, Rev, ViewResults},
+    FutonResult,
+};
+
+const LOCAL_PREFIX: &str = "_local/";
+
+/// Documents that are never replicated, such as replication checkpoints.
+///
+/// IDs can be given with or without the `_local/` prefix.
+pub struct LocalDocuments<'db> {
+    client: &'db Transport,
+    url: &'db Url,
+    db_name: &'db str,
+    credentials: &'db Credentials,
+}
+
+impl<'db> LocalDocuments<'db> {
+    pub fn new(
+        client: &'db Transport,
+        url: &'db Url,
+        db_name: &'db str,
+        credentials: &'db Credentials,
+    ) -> Self {
+        Self {
+            client,
+            url,
+            db_name,
+            credentials,
+        }
+    }
+}
+
+impl<'db> LocalDocuments<'db> {
+    #[tracing::instrument(skip(self))]
+    pub async fn get<D: Document>(&self, id: &str) -> FutonResult<Option<D>> {
+        let mut client = self.client.clone();
+
+        let req = self.request(id)?.method(Method::GET)?;
+
+        let res = client.call(req).await?;
+
+        if res.is_not_found() {
+            return Ok(None);
+        }
+
+        let doc = res.error_for_status()?.into_body().json()?;
+        Ok(Some(doc))
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn put<D: Document + Debug>(&self, mut doc: D) -> FutonResult<D> {
+        let mut client = self.client.clone();
+
+        let req = self.request(doc.id())?.method(Method::PUT)?.json(&doc)?;
+
+        let res = client.call(req).await?;
+
+        let DocumentOperation { id, rev, .. } = res.error_for_status()?.into_body().json()?;
+
+        doc.set_id(id);
+        if let Some(rev) = rev {
+            doc.set_rev(rev);
+        }
+        Ok(doc)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn delete<D: Document + Debug>(&self, mut doc: D) -> FutonResult<D> {
+        let mut client = self.client.clone();
+
+        let req = self.request(doc.id())?.method(Method::DELETE)?;
+        let req = match doc.rev() {
+            Some(rev) => req.query_param("rev", rev),
+            None => req,
+        };
+
+        let res = client.call(req).await?;
+
+        let DocumentOperation { rev, .. } = res.error_for_status()?.into_body().json()?;
+
+        if let Some(rev) = rev {
+            doc.set_rev(rev);
+        }
+        Ok(doc)
+    }
+
+    /// Lists local documents. CouchDB does not report `offset` and `total_rows`
+    /// for this query, so they are always zero.
+    #[tracing::instrument(skip(self))]
+    pub async fn list<D: Document + Debug>(
+        &self,
+        params: ViewParams,
+    ) -> FutonResult<ViewResults<Rev, D>> {
+        DesignDocuments::new(self.client, self.url, None, self.db_name, self.credentials)
+            .execute_builtin_view("_local_docs", params)
+            .await
+    }
+
+    #[inline]
+    fn request(&self, id: &str) -> FutonResult<FutonRequest> {
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .database(self.db_name)
+            .segment("_local")
+            .segment(id.strip_prefix(LOCAL_PREFIX).unwrap_or(id));
+        Ok(req)
+    }
+}
@@ -1,18 +1,33 @@
-use crate::{response::ServerInstanceInfo, FutonResult};
+use crate::{
+    feed,
+    request::{
+        AddNode, ClusterSetupAction, DbUpdatesParams, EnableCluster, EnableSingleNode, Feed,
+        FinishCluster, Node,
+    },
+    response::{
+        self, ClusterSetupState, ClusterSetupStatus, ConfigSection, DbUpdate, DbUpdates,
+        Membership, NodeConfig, ServerInstanceInfo,
+    },
+    FutonResult,
+};
 use futon_core::{Credentials, Service};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt, TryStreamExt,
+};
 
-use futon_core::{FutonClient, FutonRequest};
+use futon_core::{FutonRequest, Transport};
 use http::Method;
 use url::Url;
 
 pub struct Meta {
-    client: FutonClient,
+    client: Transport,
     url: Url,
     credentials: Credentials,
 }
 
 impl Meta {
-    pub(crate) fn new(client: FutonClient, url: Url, credentials: Credentials) -> Self {
+    pub(crate) fn new(client: Transport, url: Url, credentials: Credentials) -> Self {
         Self {
             client,
             url,
@@ -45,4 +60,244 @@ impl Meta {
         let info = res.error_for_status()?.body().json()?;
         Ok(info)
     }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn membership(&self) -> FutonResult<Membership> {
+        let mut client = self.client.clone();
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .path("_membership");
+
+        let res = client.call(req).await?;
+
+        let membership = res.error_for_status()?.into_body().json()?;
+        Ok(membership)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn cluster_setup_state(
+        &self,
+        ensure_dbs_exist: &[&str],
+    ) -> FutonResult<ClusterSetupState> {
+        let mut client = self.client.clone();
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .path("_cluster_setup");
+
+        let req = if ensure_dbs_exist.is_empty() {
+            req
+        } else {
+            req.query_param(
+                "ensure_dbs_exist",
+                &serde_json::to_string(ensure_dbs_exist)?,
+            )
+        };
+
+        let res = client.call(req).await?;
+
+        let ClusterSetupStatus { state } = res.error_for_status()?.into_body().json()?;
+        Ok(state)
+    }
+
+    #[tracing::instrument(skip(self, action))]
+    pub async fn cluster_setup(&self, action: impl Into<ClusterSetupAction>) -> FutonResult<()> {
+        let mut client = self.client.clone();
+        let req = FutonRequest::new(self.url.clone())?
+            .method(Method::POST)?
+            .credentials(self.credentials.clone())
+            .path("_cluster_setup")
+            .json(action.into())?;
+
+        let res = client.call(req).await?;
+
+        let _: response::Ok = res.error_for_status()?.into_body().json()?;
+        Ok(())
+    }
+
+    #[inline]
+    pub async fn enable_single_node(&self, action: EnableSingleNode) -> FutonResult<()> {
+        self.cluster_setup(action).await
+    }
+
+    #[inline]
+    pub async fn enable_cluster(&self, action: EnableCluster) -> FutonResult<()> {
+        self.cluster_setup(action).await
+    }
+
+    #[inline]
+    pub async fn add_node(&self, action: AddNode) -> FutonResult<()> {
+        self.cluster_setup(action).await
+    }
+
+    #[inline]
+    pub async fn finish_cluster(&self, action: FinishCluster) -> FutonResult<()> {
+        self.cluster_setup(action).await
+    }
+
+    /// Streams database creation, update and deletion events.
+    ///
+    /// A `normal` feed ends after the current events, while `longpoll` keeps
+    /// polling from the last received sequence and `continuous` keeps a single
+    /// connection open until the server closes it.
+    #[tracing::instrument(skip(self))]
+    pub fn db_updates(&self, params: DbUpdatesParams) -> BoxStream<'static, FutonResult<DbUpdate>> {
+        let client = self.client.clone();
+        let url = self.url.clone();
+        let credentials = self.credentials.clone();
+
+        let request = move |params: &DbUpdatesParams| -> FutonResult<FutonRequest> {
+            let req = FutonRequest::new(url.clone())?
+                .credentials(credentials.clone())
+                .path("_db_updates")
+                .query_string(params)?;
+            Ok(req)
+        };
+
+        match params.feed {
+            Feed::Normal | Feed::Longpoll => {
+                let longpoll = params.feed == Feed::Longpoll;
+                stream::try_unfold(Some(params), move |params| {
+                    let mut client = client.clone();
+                    let req = params.as_ref().map(&request).transpose();
+                    async move {
+                        let (mut params, req) = match (params, req?) {
+                            (Some(params), Some(req)) => (params, req),
+                            _ => return FutonResult::Ok(None),
+                        };
+
+                        let res = client.call(req).await?;
+                        let DbUpdates { results, last_seq } =
+                            res.error_for_status()?.into_body().json()?;
+
+                        params.since = Some(last_seq);
+                        let next = longpoll.then_some(params);
+                        Ok(Some((stream::iter(results.into_iter().map(Ok)), next)))
+                    }
+                })
+                .try_flatten()
+                .boxed()
+            }
+            Feed::Continuous => {
+                let mut client = client;
+                let req = request(&params);
+                stream::once(async move {
+                    let res = client.call(req?.streaming()).await?;
+                    let stream = res.error_for_status()?.into_stream();
+                    FutonResult::Ok(feed::json_lines(stream))
+                })
+                .try_flatten()
+                .boxed()
+            }
+        }
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn config(&self, node: &Node) -> FutonResult<NodeConfig> {
+        let mut client = self.client.clone();
+        let req = self.config_request(node)?;
+
+        let res = client.call(req).await?;
+
+        let config = res.error_for_status()?.into_body().json()?;
+        Ok(config)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn config_section(&self, node: &Node, section: &str) -> FutonResult<ConfigSection> {
+        let mut client = self.client.clone();
+        let req = self.config_request(node)?.segment(section);
+
+        let res = client.call(req).await?;
+
+        let section = res.error_for_status()?.into_body().json()?;
+        Ok(section)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn config_value(
+        &self,
+        node: &Node,
+        section: &str,
+        key: &str,
+    ) -> FutonResult<Option<String>> {
+        let mut client = self.client.clone();
+        let req = self.config_request(node)?.segment(section).segment(key);
+
+        let res = client.call(req).await?;
+
+        if res.is_not_found() {
+            return Ok(None);
+        }
+
+        let value = res.error_for_status()?.into_body().json()?;
+        Ok(Some(value))
+    }
+
+    /// Sets a configuration value, returning the previous one if it was set.
+    #[tracing::instrument(skip(self))]
+    pub async fn set_config_value(
+        &self,
+        node: &Node,
+        section: &str,
+        key: &str,
+        value: &str,
+    ) -> FutonResult<Option<String>> {
+        let mut client = self.client.clone();
+        let req = self
+            .config_request(node)?
+            .method(Method::PUT)?
+            .segment(section)
+            .segment(key)
+            .json(value)?;
+
+        let res = client.call(req).await?;
+
+        let previous: String = res.error_for_status()?.into_body().json()?;
+        Ok(Some(previous).filter(|previous| !previous.is_empty()))
+    }
+
+    /// Deletes a configuration value, returning the one that was removed.
+    #[tracing::instrument(skip(self))]
+    pub async fn delete_config_value(
+        &self,
+        node: &Node,
+        section: &str,
+        key: &str,
+    ) -> FutonResult<String> {
+        let mut client = self.client.clone();
+        let req = self
+            .config_request(node)?
+            .method(Method::DELETE)?
+            .segment(section)
+            .segment(key);
+
+        let res = client.call(req).await?;
+
+        let previous = res.error_for_status()?.into_body().json()?;
+        Ok(previous)
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn reload_config(&self, node: &Node) -> FutonResult<()> {
+        let mut client = self.client.clone();
+        let req = self
+            .config_request(node)?
+            .method(Method::POST)?
+            .segment("_reload");
+
+        let res = client.call(req).await?;
+
+        let _: response::Ok = res.error_for_status()?.into_body().json()?;
+        Ok(())
+    }
+
+    #[inline]
+    fn config_request(&self, node: &Node) -> FutonResult<FutonRequest> {
+        let req = FutonRequest::new(self.url.clone())?
+            .credentials(self.credentials.clone())
+            .path("_node")
+            .segment(&node.to_string())
+            .segment("_config");
+        Ok(req)
+    }
 }
@@ -1,4 +1,7 @@
-use serde::Serialize;
+use std::{fmt::Display, time::Duration};
+
+use secstr::SecUtf8;
+use serde::{Serialize, Serializer};
 
 use crate::document::Document;
 
@@ -53,6 +56,172 @@ impl<D: Document> From<D> for CopyDestination {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct WriteOptions {
+    /// Lets the server acknowledge the write before committing it to disk.
+    pub batch: bool,
+    pub new_edits: bool,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            batch: false,
+            new_edits: true,
+        }
+    }
+}
+
+impl WriteOptions {
+    pub fn batch() -> Self {
+        Self {
+            batch: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn replicated() -> Self {
+        Self {
+            new_edits: false,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn to_query(&self) -> Vec<(&'static str, &'static str)> {
+        let mut query = Vec::new();
+        if self.batch {
+            query.push(("batch", "ok"));
+        }
+        if !self.new_edits {
+            query.push(("new_edits", "false"));
+        }
+        query
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn backoff(&self, attempt: usize) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1) as u32);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GetOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub attachments: bool,
+    #[serde(
+        rename = "att_encoding_info",
+        skip_serializing_if = "std::ops::Not::not"
+    )]
+    pub attachments_encoding_info: bool,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "json_encoded"
+    )]
+    pub atts_since: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub conflicts: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deleted_conflicts: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub latest: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub local_seq: bool,
+    /// Shorthand for `conflicts`, `deleted_conflicts` and `revs_info`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub meta: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub revs: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub revs_info: bool,
+}
+
+impl GetOptions {
+    pub fn rev(rev: impl ToString) -> Self {
+        Self {
+            rev: Some(rev.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+fn json_encoded<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Serialize,
+    S: Serializer,
+{
+    let json = serde_json::to_string(value).map_err(serde::ser::Error::custom)?;
+    serializer.serialize_str(&json)
+}
+
+fn unsecure<S: Serializer>(secret: &SecUtf8, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(secret.unsecure())
+}
+
+fn unsecure_option<S: Serializer>(
+    secret: &Option<SecUtf8>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match secret {
+        Some(secret) => unsecure(secret, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OpenRevs {
+    All,
+    Revs(Vec<String>),
+}
+
+impl OpenRevs {
+    pub(crate) fn to_query(&self) -> Result<String, serde_json::Error> {
+        match self {
+            OpenRevs::All => Ok("all".to_string()),
+            OpenRevs::Revs(revs) => serde_json::to_string(revs),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BulkDocsParams {
+    pub new_edits: bool,
+}
+
+impl Default for BulkDocsParams {
+    fn default() -> Self {
+        Self { new_edits: true }
+    }
+}
+
+impl BulkDocsParams {
+    pub fn replicated() -> Self {
+        Self { new_edits: false }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct ViewParams {
     pub conflicts: bool,
@@ -87,20 +256,15 @@ pub struct ViewParams {
     pub update_seq: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Default, Serialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Update {
+    #[default]
     True,
     False,
     Lazy,
 }
 
-impl Default for Update {
-    fn default() -> Self {
-        Self::True
-    }
-}
-
 impl Default for ViewParams {
     fn default() -> Self {
         Self {
@@ -128,3 +292,190 @@ impl Default for ViewParams {
         }
     }
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Node {
+    #[default]
+    Local,
+    Named(String),
+}
+
+impl Node {
+    pub fn named(name: impl ToString) -> Self {
+        Self::Named(name.to_string())
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Node::Local => "_local",
+            Node::Named(name) => name,
+        };
+
+        Display::fmt(s, f)
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ClusterSetupAction {
+    EnableSingleNode(EnableSingleNode),
+    EnableCluster(EnableCluster),
+    AddNode(AddNode),
+    FinishCluster(FinishCluster),
+}
+
+impl From<EnableSingleNode> for ClusterSetupAction {
+    fn from(action: EnableSingleNode) -> Self {
+        Self::EnableSingleNode(action)
+    }
+}
+
+impl From<EnableCluster> for ClusterSetupAction {
+    fn from(action: EnableCluster) -> Self {
+        Self::EnableCluster(action)
+    }
+}
+
+impl From<AddNode> for ClusterSetupAction {
+    fn from(action: AddNode) -> Self {
+        Self::AddNode(action)
+    }
+}
+
+impl From<FinishCluster> for ClusterSetupAction {
+    fn from(action: FinishCluster) -> Self {
+        Self::FinishCluster(action)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnableSingleNode {
+    pub username: String,
+    #[serde(serialize_with = "unsecure")]
+    pub password: SecUtf8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ensure_dbs_exist: Option<Vec<String>>,
+}
+
+impl EnableSingleNode {
+    pub fn new(username: impl ToString, password: impl ToString) -> Self {
+        Self {
+            username: username.to_string(),
+            password: SecUtf8::from(password.to_string()),
+            bind_address: None,
+            port: None,
+            ensure_dbs_exist: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EnableCluster {
+    pub username: String,
+    #[serde(serialize_with = "unsecure")]
+    pub password: SecUtf8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub node_count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_node: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_current_user: Option<String>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "unsecure_option"
+    )]
+    pub remote_current_password: Option<SecUtf8>,
+}
+
+impl EnableCluster {
+    pub fn new(username: impl ToString, password: impl ToString) -> Self {
+        Self {
+            username: username.to_string(),
+            password: SecUtf8::from(password.to_string()),
+            bind_address: None,
+            port: None,
+            node_count: None,
+            remote_node: None,
+            remote_current_user: None,
+            remote_current_password: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AddNode {
+    pub host: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    pub username: String,
+    #[serde(serialize_with = "unsecure")]
+    pub password: SecUtf8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl AddNode {
+    pub fn new(host: impl ToString, username: impl ToString, password: impl ToString) -> Self {
+        Self {
+            host: host.to_string(),
+            port: None,
+            username: username.to_string(),
+            password: SecUtf8::from(password.to_string()),
+            name: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct FinishCluster {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ensure_dbs_exist: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feed {
+    #[default]
+    Normal,
+    Longpoll,
+    Continuous,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DbUpdatesParams {
+    pub feed: Feed,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<String>,
+    /// Milliseconds to wait for new events before the server closes the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// Milliseconds between empty lines sent by the server to keep the connection alive.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat: Option<u64>,
+}
+
+impl DbUpdatesParams {
+    pub fn longpoll() -> Self {
+        Self {
+            feed: Feed::Longpoll,
+            ..Default::default()
+        }
+    }
+
+    pub fn continuous() -> Self {
+        Self {
+            feed: Feed::Continuous,
+            ..Default::default()
+        }
+    }
+}
@@ -1,9 +1,9 @@
 use std::collections::HashMap;
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 
-use crate::document::Document;
+use crate::{ddoc::QueryServer, document::Document, error::BulkDocError};
 
 #[derive(Debug, Deserialize)]
 pub struct ServerInstanceInfo {
@@ -20,6 +20,54 @@ pub struct ServerInstanceInfoVendor {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct Membership {
+    pub all_nodes: Vec<String>,
+    pub cluster_nodes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClusterSetupStatus {
+    pub state: ClusterSetupState,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ClusterSetupState {
+    ClusterDisabled,
+    SingleNodeDisabled,
+    SingleNodeEnabled,
+    ClusterEnabled,
+    ClusterFinished,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DbUpdates {
+    pub results: Vec<DbUpdate>,
+    pub last_seq: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DbUpdate {
+    pub db_name: String,
+    #[serde(rename = "type")]
+    pub kind: DbUpdateKind,
+    pub seq: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DbUpdateKind {
+    Created,
+    Updated,
+    Deleted,
+    DdocUpdated,
+}
+
+pub type NodeConfig = HashMap<String, ConfigSection>;
+
+pub type ConfigSection = HashMap<String, String>;
+
 #[derive(Debug, Deserialize)]
 pub struct DatabaseInfo {
     pub cluster: ClusterReplicationParams,
@@ -35,6 +83,24 @@ pub struct DatabaseInfo {
     pub update_seq: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct PurgeResult {
+    pub purge_seq: Option<String>,
+    pub purged: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+pub struct RevsDiff {
+    pub missing: Vec<String>,
+    #[serde(default)]
+    pub possible_ancestors: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MissingRevs {
+    pub missing_revs: HashMap<String, Vec<String>>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ClusterReplicationParams {
     pub n: usize,
@@ -50,6 +116,34 @@ pub struct Sizes {
     pub file: usize,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct DesignDocumentInfo {
+    pub name: String,
+    pub view_index: ViewIndexInfo,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ViewIndexInfo {
+    pub compact_running: bool,
+    pub language: QueryServer,
+    pub purge_seq: Value,
+    pub signature: String,
+    pub sizes: Sizes,
+    pub update_seq: Value,
+    pub updater_running: bool,
+    pub waiting_clients: usize,
+    pub waiting_commit: bool,
+}
+
+/// The fields of an `_active_tasks` entry needed to recognise compactions.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ActiveTask {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub database: Option<String>,
+    pub design_document: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Ok {
     pub ok: bool,
@@ -64,7 +158,151 @@ pub struct Rev {
 pub struct DocumentOperation {
     pub id: String,
     pub ok: bool,
+    /// Missing when the write was accepted in batch mode.
+    pub rev: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BulkDocResult {
+    pub id: String,
+    pub rev: Option<String>,
+    pub error: Option<String>,
+    pub reason: Option<String>,
+}
+
+impl BulkDocResult {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+
+    pub fn into_result(self) -> Result<Rev, BulkDocError> {
+        match (self.error, self.rev) {
+            (None, Some(rev)) => Ok(Rev { rev }),
+            (error, _) => Err(BulkDocError {
+                id: self.id,
+                error: error.unwrap_or_else(|| "unknown_error".to_string()),
+                reason: self.reason.unwrap_or_default(),
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WithConflicts<D> {
+    #[serde(flatten)]
+    pub doc: D,
+    #[serde(rename = "_conflicts", default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+}
+
+impl<D> WithConflicts<D> {
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+
+    pub fn into_inner(self) -> D {
+        self.doc
+    }
+}
+
+/// A document fetched along with the metadata requested through
+/// [`GetOptions`](crate::request::GetOptions).
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FetchedDocument<D> {
+    #[serde(flatten)]
+    pub doc: D,
+    #[serde(rename = "_revisions", skip_serializing_if = "Option::is_none")]
+    pub revisions: Option<Revisions>,
+    #[serde(rename = "_revs_info", skip_serializing_if = "Option::is_none")]
+    pub revs_info: Option<Vec<RevInfo>>,
+    #[serde(rename = "_conflicts", default, skip_serializing_if = "Vec::is_empty")]
+    pub conflicts: Vec<String>,
+    #[serde(
+        rename = "_deleted_conflicts",
+        default,
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub deleted_conflicts: Vec<String>,
+    #[serde(rename = "_local_seq", skip_serializing_if = "Option::is_none")]
+    pub local_seq: Option<Value>,
+}
+
+impl<D> FetchedDocument<D> {
+    pub fn into_inner(self) -> D {
+        self.doc
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Revisions {
+    pub start: usize,
+    pub ids: Vec<String>,
+}
+
+impl Revisions {
+    /// Full revision strings, from the most recent to the oldest known one.
+    pub fn revs(&self) -> Vec<String> {
+        self.ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| format!("{}-{}", self.start.saturating_sub(i), id))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RevInfo {
     pub rev: String,
+    pub status: RevStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RevStatus {
+    Available,
+    Missing,
+    Deleted,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Conditional<D> {
+    Modified(D),
+    NotModified,
+}
+
+impl<D> Conditional<D> {
+    pub fn is_modified(&self) -> bool {
+        matches!(self, Conditional::Modified(_))
+    }
+
+    pub fn modified(self) -> Option<D> {
+        match self {
+            Conditional::Modified(doc) => Some(doc),
+            Conditional::NotModified => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Updated<D> {
+    pub doc: D,
+    pub attempts: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpenRev<D> {
+    Ok(D),
+    Missing(String),
+}
+
+impl<D> OpenRev<D> {
+    pub fn ok(self) -> Option<D> {
+        match self {
+            OpenRev::Ok(doc) => Some(doc),
+            OpenRev::Missing(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -77,6 +315,16 @@ pub struct Tombstone {
     pub deleted: bool,
 }
 
+impl Tombstone {
+    pub fn new(id: impl ToString, rev: impl ToString) -> Self {
+        Self {
+            id: id.to_string(),
+            rev: rev.to_string(),
+            deleted: true,
+        }
+    }
+}
+
 impl Document for Tombstone {
     fn id(&self) -> &str {
         &self.id
@@ -99,14 +347,16 @@ impl Document for Tombstone {
 
 #[derive(Debug, Deserialize)]
 pub struct ViewResults<V, T> {
+    #[serde(default, deserialize_with = "null_as_default")]
     pub offset: usize,
+    #[serde(default, deserialize_with = "null_as_default")]
     pub total_rows: usize,
     pub update_seq: Option<String>,
     pub rows: Vec<ViewRow<V, T>>,
 }
 
 impl<V, T> ViewResults<V, T> {
-    pub fn iter(&self) -> std::slice::Iter<ViewRow<V, T>> {
+    pub fn iter(&self) -> std::slice::Iter<'_, ViewRow<V, T>> {
         self.rows.iter()
     }
 }
@@ -128,3 +378,11 @@ pub struct ViewRow<V, T> {
     pub value: V,
     pub doc: Option<T>,
 }
+
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
@@ -0,0 +1,74 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use bytes::Bytes;
+use futon::{Futon, FutonRequest, FutonResponse};
+use futon_core::Service;
+use http::{HeaderMap, StatusCode};
+
+/// The requests a mock transport received, in order.
+pub type Requests = Arc<Mutex<Vec<FutonRequest>>>;
+
+pub fn response(status: StatusCode, body: &'static str) -> FutonResponse {
+    FutonResponse::new(
+        status,
+        HeaderMap::new(),
+        Bytes::from_static(body.as_bytes()).into(),
+    )
+}
+
+/// Answers with the given responses in turn, repeating the last one.
+pub fn in_turn(
+    responses: &'static [(StatusCode, &'static str)],
+) -> impl Fn(&FutonRequest) -> FutonResponse + Clone + Send + 'static {
+    let calls = Arc::new(AtomicUsize::new(0));
+    move |_req| {
+        let call = calls.fetch_add(1, Ordering::SeqCst);
+        let (status, body) = responses[call.min(responses.len() - 1)];
+        response(status, body)
+    }
+}
+
+/// A transport answering every request with `respond` instead of reaching
+/// CouchDB, recording the requests it receives.
+pub fn mock_transport<F>(
+    respond: F,
+) -> (
+    impl Service<
+            FutonRequest,
+            Response = FutonResponse,
+            Error = futon_core::Error,
+            Future = impl Send,
+        > + Clone,
+    Requests,
+)
+where
+    F: Fn(&FutonRequest) -> FutonResponse + Clone + Send + 'static,
+{
+    let requests = Requests::default();
+    let service = tower::service_fn({
+        let requests = Arc::clone(&requests);
+        move |req: FutonRequest| {
+            let res = respond(&req);
+            requests.lock().unwrap().push(req);
+            async { Ok(res) }
+        }
+    });
+    (service, requests)
+}
+
+/// A client for `http://couchdb.invalid:5984` sending its requests through
+/// [`mock_transport`].
+pub fn mock_futon<F>(respond: F) -> (Futon, Requests)
+where
+    F: Fn(&FutonRequest) -> FutonResponse + Clone + Send + 'static,
+{
+    let (service, requests) = mock_transport(respond);
+    let futon = Futon::new_with_transport(
+        url::Url::parse("http://couchdb.invalid:5984").unwrap(),
+        service,
+    );
+    (futon, requests)
+}
@@ -2,13 +2,25 @@ use std::future::Future;
 
 use futon::{db::Database, Credentials, Futon};
 
+// Each test binary only uses some of the helpers, either talking to a
+// CouchDB container or to a mock transport.
+#[allow(dead_code)]
 mod document;
+#[allow(dead_code)]
 mod image;
+#[allow(dead_code)]
+mod mock;
 
+#[allow(unused_imports)]
 pub use document::TestDocument;
+#[allow(unused_imports)]
+pub use image::{PASSWORD, USERNAME};
+#[allow(unused_imports)]
+pub use mock::{in_turn, mock_futon, mock_transport, response, Requests};
 
 pub type TestResult = Result<(), Box<dyn std::error::Error>>;
 
+#[allow(dead_code)]
 pub async fn with_couchdb<Test, Fut>(test: Test) -> TestResult
 where
     Fut: Future<Output = TestResult>,
@@ -35,6 +47,7 @@ where
     Ok(())
 }
 
+#[allow(dead_code)]
 pub async fn with_db<Test, Fut>(test: Test) -> TestResult
 where
     Fut: Future<Output = TestResult>,
@@ -1,11 +1,15 @@
 mod common;
 
+use std::{collections::HashMap, time::Duration};
+
 use common::TestDocument;
 
 use futon::{
+    document::JsonDocument,
     error::FutonError,
     request::{DatabaseCreationParams, ViewParams},
 };
+use http::StatusCode;
 
 #[tokio::test]
 async fn it_creates_and_deletes_a_db() {
@@ -116,3 +120,229 @@ async fn it_lists_all_documents() {
     .await
     .unwrap();
 }
+
+#[tokio::test]
+async fn it_compacts_a_db() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let doc = TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        };
+
+        let docs = db.documents();
+        let mut doc = docs.create(doc).await?;
+        doc.message = "Updated message".to_string();
+        docs.create_or_update(doc).await?;
+
+        db.compact().await?;
+        db.wait_for_compaction(Duration::from_millis(100), Duration::from_secs(30))
+            .await?;
+        db.view_cleanup().await?;
+
+        let info = db.info().await?;
+        assert!(!info.compact_running);
+        assert_eq!(info.doc_count, 1);
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_compacts_a_design_document() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let mut ddoc = JsonDocument::new("_design/app");
+        ddoc.insert(
+            "views".to_string(),
+            serde_json::json!({ "by_message": { "map": "function (doc) { emit(doc.message); }" } }),
+        );
+        let docs = db.documents();
+        docs.create(ddoc).await?;
+        docs.create(TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        })
+        .await?;
+
+        db.compact_design("app").await?;
+        db.wait_for_design_compaction("app", Duration::from_millis(100), Duration::from_secs(30))
+            .await?;
+
+        let info = db.design_info("_design/app").await?;
+        assert!(!info.view_index.compact_running);
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_waits_for_the_compaction_tasks_of_the_db() {
+    let (futon, requests) = common::mock_futon(common::in_turn(&[
+        (
+            StatusCode::OK,
+            r#"[{"type":"database_compaction","database":"shards/00000000-7fffffff/test.1681234567","node":"couchdb@127.0.0.1","progress":10}]"#,
+        ),
+        (
+            StatusCode::OK,
+            r#"[{"type":"database_compaction","database":"shards/80000000-ffffffff/test.1681234567","node":"couchdb@127.0.0.1","progress":60}]"#,
+        ),
+        (
+            StatusCode::OK,
+            r#"[{"type":"database_compaction","database":"shards/00000000-7fffffff/other.1681234567","node":"couchdb@127.0.0.1","progress":0},{"type":"indexer","database":"shards/00000000-7fffffff/test.1681234567","design_document":"_design/app","node":"couchdb@127.0.0.1","progress":0}]"#,
+        ),
+    ]));
+
+    futon
+        .db("test")
+        .unwrap()
+        .wait_for_compaction(Duration::from_secs(1), Duration::from_secs(5))
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(
+        requests[0].url().as_str(),
+        "http://couchdb.invalid:5984/_active_tasks"
+    );
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_stops_waiting_for_compaction_after_the_timeout() {
+    let (futon, _) = common::mock_futon(|_| {
+        common::response(
+            StatusCode::OK,
+            r#"[{"type":"view_compaction","database":"shards/00000000-ffffffff/test.1681234567","design_document":"_design/app","node":"couchdb@127.0.0.1","progress":0}]"#,
+        )
+    });
+
+    let err = futon
+        .db("test")
+        .unwrap()
+        .wait_for_design_compaction("app", Duration::from_secs(1), Duration::from_secs(5))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        FutonError::Client(futon_core::Error::Timeout(timeout)) if timeout == Duration::from_secs(5)
+    ));
+}
+
+#[tokio::test]
+async fn it_manages_local_documents() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let local = db.local_docs();
+
+        let nope = local.get::<TestDocument>("checkpoint").await?;
+        assert!(nope.is_none());
+
+        let doc = TestDocument {
+            id: "checkpoint".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        };
+        let mut doc = local.put(doc).await?;
+        assert_eq!(&doc.id, "_local/checkpoint");
+        assert!(doc.rev.is_some());
+
+        doc.message = "Updated message".to_string();
+        let doc = local.put(doc).await?;
+
+        let fetched = local
+            .get::<TestDocument>("_local/checkpoint")
+            .await?
+            .unwrap();
+        assert_eq!(fetched, doc);
+
+        let all_docs = db.all_docs::<TestDocument>(Default::default()).await?;
+        assert_eq!(all_docs.total_rows, 0);
+
+        let local_docs = local
+            .list::<TestDocument>(ViewParams {
+                include_docs: true,
+                ..Default::default()
+            })
+            .await?;
+        let mut iter = local_docs.into_iter();
+        assert_eq!(iter.next().and_then(|row| row.doc), Some(doc));
+
+        let doc = local.get::<TestDocument>("checkpoint").await?.unwrap();
+        local.delete(doc).await?;
+
+        let nope = local.get::<TestDocument>("checkpoint").await?;
+        assert!(nope.is_none());
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_purges_documents() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let doc = TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        };
+
+        let docs = db.documents();
+        let doc = docs.create(doc).await?;
+        let rev = doc.rev.clone().unwrap();
+
+        let revs = HashMap::from([("test".to_string(), vec![rev.clone()])]);
+        let missing = db.missing_revs(revs.clone()).await?;
+        assert!(missing.is_empty());
+
+        let unknown = HashMap::from([("test".to_string(), vec!["2-abc".to_string()])]);
+        let diff = db.revs_diff(unknown).await?;
+        assert_eq!(diff["test"].missing, vec!["2-abc".to_string()]);
+        assert_eq!(diff["test"].possible_ancestors, vec![rev.clone()]);
+
+        let purged = db.purge(revs.clone()).await?;
+        assert_eq!(purged.purged["test"], vec![rev]);
+
+        let missing = db.missing_revs(revs).await?;
+        assert!(missing.contains_key("test"));
+
+        let nope = docs.get::<TestDocument>("test").await?;
+        assert!(nope.is_none());
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_configures_revision_limits() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        assert_eq!(db.revs_limit().await?, 1000);
+        db.set_revs_limit(10).await?;
+        assert_eq!(db.revs_limit().await?, 10);
+
+        assert_eq!(db.purged_infos_limit().await?, 1000);
+        db.set_purged_infos_limit(100).await?;
+        assert_eq!(db.purged_infos_limit().await?, 100);
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
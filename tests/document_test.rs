@@ -1,4 +1,16 @@
-use futon::{document::Document, error::FutonError, request::CopyDestination, response::Tombstone};
+use futon::{
+    document::{Document, JsonDocument},
+    error::FutonError,
+    request::{BulkDocsParams, CopyDestination, GetOptions, OpenRevs, RetryPolicy, WriteOptions},
+    response::{Conditional, RevStatus, Tombstone},
+    FutonResponse, ResponseCache,
+};
+use futon_core::X_COUCH_REQUEST_ID;
+use http::{HeaderMap, Method, StatusCode};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
 
 use crate::common::TestDocument;
 
@@ -251,3 +263,383 @@ async fn it_copies_to_an_existing_document() {
     .await
     .unwrap();
 }
+
+#[tokio::test]
+async fn it_resolves_conflicts() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let doc = TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: "Hello".to_string(),
+        };
+
+        let docs = db.documents();
+        let doc = docs.create(doc).await?;
+
+        let conflicting = TestDocument {
+            id: "test".to_string(),
+            rev: Some("1-00000000000000000000000000000000".to_string()),
+            message: "Futon!".to_string(),
+        };
+        docs.bulk_docs(&[conflicting], BulkDocsParams::replicated())
+            .await?;
+
+        let conflicted = docs
+            .get_with_conflicts::<TestDocument>(doc.id())
+            .await?
+            .unwrap();
+        assert_eq!(conflicted.conflicts.len(), 1);
+
+        let leaves = docs
+            .open_revs::<TestDocument>(doc.id(), OpenRevs::All)
+            .await?;
+        assert_eq!(leaves.len(), 2);
+
+        let resolved = docs
+            .resolve_conflicts::<TestDocument, _>(doc.id(), |mut winner, losers| {
+                let mut messages = vec![winner.message.clone()];
+                messages.extend(losers.into_iter().map(|doc| doc.message));
+                messages.sort();
+                winner.message = messages.join(" ");
+                winner
+            })
+            .await?
+            .unwrap();
+        assert_eq!(&resolved.message, "Futon! Hello");
+        assert!(resolved.rev.as_deref().unwrap().starts_with("2-"));
+
+        let resolved = docs
+            .get_with_conflicts::<TestDocument>(doc.id())
+            .await?
+            .unwrap();
+        assert!(!resolved.has_conflicts());
+        assert_eq!(&resolved.doc.message, "Futon! Hello");
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_only_sends_the_get_options_set() {
+    let (futon, requests) = common::mock_futon(|_| {
+        common::response(
+            StatusCode::OK,
+            r#"{"_id":"test","_rev":"1-a","message":""}"#,
+        )
+    });
+    let db = futon.db("test").unwrap();
+    let docs = db.documents();
+
+    docs.get_with::<TestDocument>("test", GetOptions::default())
+        .await
+        .unwrap();
+    docs.get_with::<TestDocument>(
+        "test",
+        GetOptions {
+            conflicts: true,
+            ..GetOptions::rev("1-a")
+        },
+    )
+    .await
+    .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0].url().query(), None);
+    assert_eq!(requests[1].url().query(), Some("rev=1-a&conflicts=true"));
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_retries_conflicting_updates() {
+    let puts = Arc::new(AtomicUsize::new(0));
+    let (futon, _) = common::mock_futon({
+        let puts = Arc::clone(&puts);
+        move |req| {
+            if req.http_method() == Method::GET {
+                common::response(
+                    StatusCode::OK,
+                    r#"{"_id":"test","_rev":"1-a","message":""}"#,
+                )
+            } else if puts.fetch_add(1, Ordering::SeqCst) < 2 {
+                common::response(
+                    StatusCode::CONFLICT,
+                    r#"{"error":"conflict","reason":"Document update conflict."}"#,
+                )
+            } else {
+                common::response(
+                    StatusCode::CREATED,
+                    r#"{"ok":true,"id":"test","rev":"2-b"}"#,
+                )
+            }
+        }
+    });
+    let db = futon.db("test").unwrap();
+
+    let updated = db
+        .documents()
+        .update_with::<TestDocument, _>("test", |doc| doc.message.push_str("updated"))
+        .await
+        .unwrap();
+
+    assert_eq!(updated.attempts, 3);
+    assert_eq!(puts.load(Ordering::SeqCst), 3);
+    assert_eq!(updated.doc.rev.as_deref(), Some("2-b"));
+}
+
+#[tokio::test]
+async fn it_reports_missing_documents_as_couchdb_does() {
+    let (futon, _) = common::mock_futon(|_| {
+        let mut headers = HeaderMap::new();
+        headers.insert(X_COUCH_REQUEST_ID, "a1b2c3".parse().unwrap());
+        FutonResponse::new(
+            StatusCode::NOT_FOUND,
+            headers,
+            bytes::Bytes::from_static(br#"{"error":"not_found","reason":"deleted"}"#).into(),
+        )
+    });
+    let db = futon.db("test").unwrap();
+
+    let err = db
+        .documents()
+        .update_with::<TestDocument, _>("test", |_| {})
+        .await
+        .unwrap_err();
+
+    assert!(err.is_not_found());
+    let err = err.error_response().unwrap();
+    assert_eq!(err.reason, "deleted");
+    assert_eq!(err.request_id.as_deref(), Some("a1b2c3"));
+}
+
+#[tokio::test]
+async fn it_updates_a_document_retrying_on_conflict() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let doc = TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: String::new(),
+        };
+
+        let docs = db.documents();
+        docs.create(doc).await?;
+
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            ..Default::default()
+        };
+
+        let updates = (0..5).map(|i| {
+            docs.update_with_policy::<TestDocument, _>("test", policy.clone(), move |doc| {
+                doc.message.push_str(&i.to_string())
+            })
+        });
+        let updates = futures::future::try_join_all(updates).await?;
+
+        // The updates start together, so they race on the first revision.
+        assert!(updates.iter().any(|updated| updated.attempts > 1));
+
+        let doc = docs.get::<TestDocument>("test").await?.unwrap();
+        let mut applied: Vec<_> = doc.message.chars().collect();
+        applied.sort_unstable();
+        assert_eq!(applied, ['0', '1', '2', '3', '4']);
+        assert!(doc.rev.as_deref().unwrap().starts_with("6-"));
+
+        let err = docs
+            .update_with::<TestDocument, _>("nope", |_| {})
+            .await
+            .unwrap_err();
+        assert!(err.is_not_found());
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_handles_untyped_documents() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let mut doc = JsonDocument::new("test");
+        doc.insert("message".to_string(), "Hello Futon!".into());
+        doc.insert("count".to_string(), 1.into());
+
+        let docs = db.documents();
+        let doc = docs.create(doc).await?;
+        assert_eq!(doc.id(), "test");
+        assert!(doc.rev().is_some());
+
+        let typed = docs.get::<TestDocument>("test").await?.unwrap();
+        assert_eq!(&typed.message, "Hello Futon!");
+        assert_eq!(typed.rev(), doc.rev());
+
+        let mut fetched = docs.get::<JsonDocument>("test").await?.unwrap();
+        assert_eq!(fetched, doc);
+        assert_eq!(fetched.get("count"), Some(&1.into()));
+
+        fetched.insert("count".to_string(), 2.into());
+        let updated = docs.create_or_update(fetched).await?;
+        assert_ne!(updated.rev(), doc.rev());
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_fetches_a_document_with_options() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let doc = TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        };
+
+        let docs = db.documents();
+        let mut doc = docs.create(doc).await?;
+        let first_rev = doc.rev.clone().unwrap();
+        doc.message = "Updated message".to_string();
+        let doc = docs.create_or_update(doc).await?;
+
+        let fetched = docs
+            .get_with::<TestDocument>(
+                "test",
+                GetOptions {
+                    revs: true,
+                    revs_info: true,
+                    local_seq: true,
+                    ..Default::default()
+                },
+            )
+            .await?
+            .unwrap();
+        assert_eq!(fetched.doc, doc);
+        assert!(fetched.local_seq.is_some());
+
+        let revisions = fetched.revisions.unwrap();
+        assert_eq!(revisions.start, 2);
+        assert_eq!(
+            revisions.revs(),
+            vec![doc.rev.clone().unwrap(), first_rev.clone()]
+        );
+
+        let revs_info = fetched.revs_info.unwrap();
+        assert_eq!(revs_info.len(), 2);
+        assert_eq!(revs_info[0].status, RevStatus::Available);
+
+        let first = docs
+            .get_with::<TestDocument>("test", GetOptions::rev(&first_rev))
+            .await?
+            .unwrap();
+        assert_eq!(&first.doc.message, "Hello Futon!");
+
+        let nope = docs.get_deleted::<TestDocument>("test").await?;
+        assert!(nope.is_none());
+
+        let deleted = docs.delete(doc).await?;
+        let last = docs
+            .get_deleted::<TestDocument>(deleted.id())
+            .await?
+            .unwrap();
+        assert_eq!(&last.doc.message, "Updated message");
+        assert!(last.doc.rev().unwrap().starts_with("2-"));
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_writes_documents_with_options() {
+    tracing_subscriber::fmt::init();
+
+    common::with_db(|db| async move {
+        let doc = TestDocument {
+            id: "batched".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        };
+
+        let docs = db.documents();
+        let doc = docs.create_with(doc, WriteOptions::batch()).await?;
+        assert_eq!(&doc.id, "batched");
+        assert!(doc.rev.is_none());
+
+        let doc = TestDocument {
+            id: "restored".to_string(),
+            rev: Some("3-00000000000000000000000000000000".to_string()),
+            message: "Hello Futon!".to_string(),
+        };
+        let restored = docs
+            .create_or_update_with(doc, WriteOptions::replicated())
+            .await?;
+        assert_eq!(
+            restored.rev.as_deref(),
+            Some("3-00000000000000000000000000000000")
+        );
+
+        let fetched = docs.get::<TestDocument>("restored").await?.unwrap();
+        assert_eq!(fetched, restored);
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_fetches_a_document_conditionally() {
+    tracing_subscriber::fmt::init();
+
+    common::with_couchdb(|name, futon| async move {
+        let cache = ResponseCache::new(16);
+        let futon = futon.with_response_cache(cache.clone());
+        let db = futon.db(name)?;
+        db.create(Default::default()).await?;
+
+        let doc = TestDocument {
+            id: "test".to_string(),
+            rev: None,
+            message: "Hello Futon!".to_string(),
+        };
+
+        let docs = db.documents();
+        let doc = docs.create(doc).await?;
+
+        let not_modified = docs
+            .get_if_none_match::<TestDocument>(doc.id(), doc.rev().unwrap())
+            .await?
+            .unwrap();
+        assert_eq!(not_modified, Conditional::NotModified);
+
+        let modified = docs
+            .get_if_none_match::<TestDocument>(doc.id(), "1-00000000000000000000000000000000")
+            .await?
+            .unwrap();
+        assert_eq!(modified, Conditional::Modified(doc));
+
+        let cached = docs.get::<TestDocument>("test").await?.unwrap();
+        assert_eq!(cache.len(), 1);
+        let revalidated = docs.get::<TestDocument>("test").await?.unwrap();
+        assert_eq!(cached, revalidated);
+
+        let nope = docs
+            .get_if_none_match::<TestDocument>("nope", "1-abc")
+            .await?;
+        assert!(nope.is_none());
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
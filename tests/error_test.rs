@@ -0,0 +1,120 @@
+use futon::{error::FutonError, response::BulkDocResult};
+use futon_core::ErrorResponse;
+use http::StatusCode;
+
+fn error(status: u16, error: &str, reason: &str) -> FutonError {
+    FutonError::from(ErrorResponse {
+        error: error.to_string(),
+        reason: reason.to_string(),
+        status: StatusCode::from_u16(status).unwrap(),
+        request_id: Some("a1b2c3".to_string()),
+    })
+}
+
+#[test]
+fn it_classifies_couchdb_errors() {
+    assert!(error(404, "not_found", "missing").is_not_found());
+    assert!(error(409, "conflict", "Document update conflict.").is_conflict());
+    assert!(matches!(
+        error(400, "bad_request", "Invalid rev format"),
+        FutonError::InvalidRevFormat(_)
+    ));
+    assert!(matches!(
+        error(403, "forbidden", "only admins may edit"),
+        FutonError::Forbidden(_)
+    ));
+    assert!(matches!(
+        error(412, "file_exists", "The database could not be created"),
+        FutonError::PreconditionFailed(_)
+    ));
+    assert!(matches!(
+        error(413, "too_large", "Document exceeds max_document_size"),
+        FutonError::PayloadTooLarge(_)
+    ));
+    assert!(matches!(
+        error(
+            415,
+            "bad_content_type",
+            "Content-Type must be application/json"
+        ),
+        FutonError::UnsupportedMediaType(_)
+    ));
+    assert!(matches!(
+        error(417, "expectation_failed", "rev mismatch"),
+        FutonError::ExpectationFailed(_)
+    ));
+    assert!(matches!(
+        error(429, "too_many_requests", "slow down"),
+        FutonError::TooManyRequests(_)
+    ));
+    assert!(matches!(
+        error(500, "unknown_error", "badarg"),
+        FutonError::InternalServerError(_)
+    ));
+    assert!(matches!(
+        error(
+            500,
+            "timeout",
+            "The request could not be processed in a reasonable amount of time."
+        ),
+        FutonError::Timeout(_)
+    ));
+    assert!(matches!(
+        error(503, "service_unavailable", "maintenance"),
+        FutonError::ServiceUnavailable(_)
+    ));
+    assert!(matches!(
+        error(418, "teapot", "short and stout"),
+        FutonError::UnknownError(_)
+    ));
+}
+
+#[test]
+fn it_tells_retryable_errors_apart() {
+    for status in [408, 429, 502, 503, 504] {
+        assert!(error(status, "error", "reason").is_retryable(), "{status}");
+    }
+
+    for status in [400, 401, 403, 404, 409, 412, 413, 500] {
+        assert!(!error(status, "error", "reason").is_retryable(), "{status}");
+    }
+
+    assert!(!FutonError::InvalidDatabaseName("NOPE".to_string()).is_retryable());
+}
+
+#[test]
+fn it_exposes_the_underlying_error_response() {
+    let err = error(403, "forbidden", "only admins may edit");
+    let res = err.error_response().unwrap();
+    assert_eq!(res.error, "forbidden");
+    assert_eq!(res.request_id.as_deref(), Some("a1b2c3"));
+    assert_eq!(err.status(), Some(StatusCode::FORBIDDEN));
+
+    let err = FutonError::InvalidDatabaseName("NOPE".to_string());
+    assert!(err.error_response().is_none());
+    assert!(err.status().is_none());
+}
+
+#[test]
+fn it_keeps_bulk_document_errors_as_reported() {
+    let result: BulkDocResult =
+        serde_json::from_str(r#"{"id":"doc","error":"unknown_error","reason":"function_clause"}"#)
+            .unwrap();
+    let err = FutonError::from(result.into_result().unwrap_err());
+    assert!(matches!(
+        &err,
+        FutonError::BulkDoc(err) if err.id == "doc" && err.error == "unknown_error"
+    ));
+    assert!(!err.is_conflict());
+    assert!(err.status().is_none());
+
+    let result: BulkDocResult = serde_json::from_str(
+        r#"{"id":"doc","error":"conflict","reason":"Document update conflict."}"#,
+    )
+    .unwrap();
+    assert!(FutonError::from(result.into_result().unwrap_err()).is_conflict());
+
+    let result: BulkDocResult =
+        serde_json::from_str(r#"{"id":"doc","ok":true,"rev":"1-a"}"#).unwrap();
+    assert_eq!(result.into_result().unwrap().rev, "1-a");
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use futon::{
+    layer::{ConcurrencyLimitLayer, RateLimitLayer, RetryLayer, TimeoutLayer},
+    request::RetryPolicy,
+    Futon, FutonRequest,
+};
+use futon_core::{Error, Layer, Service};
+use http::StatusCode;
+use tower::ServiceExt;
+
+fn policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(5),
+    }
+}
+
+#[tokio::test]
+async fn it_retries_idempotent_requests() {
+    let (service, requests) = common::mock_transport(common::in_turn(&[
+        (StatusCode::SERVICE_UNAVAILABLE, ""),
+        (StatusCode::TOO_MANY_REQUESTS, ""),
+        (StatusCode::OK, ""),
+    ]));
+    let service = RetryLayer::new(policy()).layer(service);
+
+    let req = FutonRequest::new("http://couchdb.invalid/db/doc").unwrap();
+    let res = service.oneshot(req).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn it_gives_up_after_the_maximum_attempts() {
+    let (service, requests) =
+        common::mock_transport(common::in_turn(&[(StatusCode::SERVICE_UNAVAILABLE, "")]));
+    let service = RetryLayer::new(policy()).layer(service);
+
+    let req = FutonRequest::new("http://couchdb.invalid/db").unwrap();
+    let res = service.oneshot(req).await.unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(requests.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn it_does_not_retry_unsafe_or_failed_requests() {
+    let (service, requests) =
+        common::mock_transport(common::in_turn(&[(StatusCode::SERVICE_UNAVAILABLE, "")]));
+    let service = RetryLayer::new(policy()).layer(service);
+    let req = FutonRequest::new("http://couchdb.invalid/db")
+        .unwrap()
+        .method("POST")
+        .unwrap();
+    service.oneshot(req).await.unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 1);
+
+    let (service, requests) =
+        common::mock_transport(common::in_turn(&[(StatusCode::CONFLICT, "")]));
+    let service = RetryLayer::new(policy()).layer(service);
+    let req = FutonRequest::new("http://couchdb.invalid/db/doc").unwrap();
+    service.oneshot(req).await.unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_times_out_slow_requests() {
+    let service = TimeoutLayer::new(Duration::from_secs(1)).layer(tower::service_fn(
+        |_req: FutonRequest| async {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok::<_, Error>(common::response(StatusCode::OK, ""))
+        },
+    ));
+
+    let req = FutonRequest::new("http://couchdb.invalid/").unwrap();
+    let err = service.oneshot(req).await.unwrap_err();
+
+    assert!(matches!(err, Error::Timeout(timeout) if timeout == Duration::from_secs(1)));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_rate_limits_requests() {
+    let (service, requests) = common::mock_transport(common::in_turn(&[(StatusCode::OK, "")]));
+    let mut service = RateLimitLayer::new(2, Duration::from_secs(1)).layer(service);
+
+    let start = tokio::time::Instant::now();
+    for _ in 0..5 {
+        let req = FutonRequest::new("http://couchdb.invalid/").unwrap();
+        service.ready().await.unwrap().call(req).await.unwrap();
+    }
+
+    assert_eq!(requests.lock().unwrap().len(), 5);
+    assert_eq!(start.elapsed(), Duration::from_secs(2));
+}
+
+/// Sends two requests at once through a transport holding each one for a
+/// while, returning how many it handled at the same time at most.
+async fn peak_in_flight(limit: Option<usize>) -> usize {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let service = tower::service_fn({
+        let peak = Arc::clone(&peak);
+        move |_req: FutonRequest| {
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            async move {
+                peak.fetch_max(
+                    in_flight.fetch_add(1, Ordering::SeqCst) + 1,
+                    Ordering::SeqCst,
+                );
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok::<_, Error>(common::response(StatusCode::OK, ""))
+            }
+        }
+    });
+    let mut futon = Futon::new_with_transport(
+        url::Url::parse("http://couchdb.invalid:5984").unwrap(),
+        service,
+    );
+    if let Some(limit) = limit {
+        futon = futon.layer(ConcurrencyLimitLayer::new(limit));
+    }
+
+    let meta = futon.meta();
+    let (a, b) = futures::join!(meta.is_up(), meta.is_up());
+    assert!(a.unwrap());
+    assert!(b.unwrap());
+
+    peak.load(Ordering::SeqCst)
+}
+
+#[tokio::test(start_paused = true)]
+async fn it_limits_concurrency_through_the_client() {
+    assert_eq!(peak_in_flight(None).await, 2);
+    assert_eq!(peak_in_flight(Some(1)).await, 1);
+}
@@ -1,5 +1,15 @@
 mod common;
 
+use std::time::Duration;
+
+use common::{PASSWORD, USERNAME};
+use futon::{
+    error::FutonError,
+    request::{AddNode, DbUpdatesParams, EnableCluster, EnableSingleNode, Node},
+    response::{ClusterSetupState, DbUpdateKind},
+};
+use futures::TryStreamExt;
+
 #[tokio::test]
 async fn it_checks_status_up() {
     tracing_subscriber::fmt::init();
@@ -32,3 +42,131 @@ async fn it_fetches_server_info() {
     .await
     .unwrap();
 }
+
+#[tokio::test]
+async fn it_reads_and_writes_node_config() {
+    tracing_subscriber::fmt::init();
+
+    common::with_couchdb(|_name, futon| async move {
+        let meta = futon.meta();
+        let node = Node::Local;
+
+        let config = meta.config(&node).await?;
+        assert!(config.contains_key("couchdb"));
+
+        let section = meta.config_section(&node, "couchdb").await?;
+        assert!(section.contains_key("max_document_size"));
+
+        let nope = meta.config_value(&node, "futon", "test").await?;
+        assert!(nope.is_none());
+
+        let previous = meta
+            .set_config_value(&node, "futon", "test", "hello")
+            .await?;
+        assert!(previous.is_none());
+
+        let previous = meta
+            .set_config_value(&node, "futon", "test", "world")
+            .await?;
+        assert_eq!(previous.as_deref(), Some("hello"));
+
+        let value = meta.config_value(&node, "futon", "test").await?;
+        assert_eq!(value.as_deref(), Some("world"));
+
+        let previous = meta.delete_config_value(&node, "futon", "test").await?;
+        assert_eq!(&previous, "world");
+
+        meta.reload_config(&node).await?;
+
+        let nope = meta.config_value(&node, "futon", "test").await?;
+        assert!(nope.is_none());
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_sets_up_a_single_node() {
+    tracing_subscriber::fmt::init();
+
+    common::with_couchdb(|_name, futon| async move {
+        let meta = futon.meta();
+
+        meta.enable_single_node(EnableSingleNode::new(USERNAME, PASSWORD))
+            .await?;
+
+        let state = meta.cluster_setup_state(&["_users", "_replicator"]).await?;
+        assert_eq!(state, ClusterSetupState::SingleNodeEnabled);
+
+        let membership = meta.membership().await?;
+        assert_eq!(membership.all_nodes.len(), 1);
+        assert_eq!(membership.all_nodes, membership.cluster_nodes);
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[tokio::test]
+async fn it_streams_db_updates() {
+    tracing_subscriber::fmt::init();
+
+    common::with_couchdb(|name, futon| async move {
+        let meta = futon.meta();
+        meta.enable_single_node(EnableSingleNode::new(USERNAME, PASSWORD))
+            .await?;
+
+        futon.db(name)?.create(Default::default()).await?;
+
+        let mut updates = meta.db_updates(DbUpdatesParams {
+            heartbeat: Some(1000),
+            ..DbUpdatesParams::continuous()
+        });
+
+        let created = tokio::time::timeout(Duration::from_secs(30), async {
+            while let Some(update) = updates.try_next().await? {
+                if update.db_name == name {
+                    return Ok(Some(update));
+                }
+            }
+
+            Ok::<_, FutonError>(None)
+        })
+        .await??
+        .unwrap();
+
+        assert_eq!(created.kind, DbUpdateKind::Created);
+        assert!(!created.seq.is_empty());
+
+        let updates = meta
+            .db_updates(DbUpdatesParams::default())
+            .try_collect::<Vec<_>>()
+            .await?;
+        assert!(updates.iter().any(|update| update.db_name == name));
+
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
+#[test]
+fn it_redacts_passwords_from_setup_actions() {
+    let mut cluster = EnableCluster::new("admin", "hunter2");
+    cluster.remote_current_password = Some("hunter3".into());
+    let actions = format!(
+        "{:?} {cluster:?} {:?}",
+        EnableSingleNode::new("admin", "hunter2"),
+        AddNode::new("couchdb-2", "admin", "hunter2"),
+    );
+
+    assert!(actions.contains("admin"));
+    assert!(!actions.contains("hunter"));
+
+    let json = serde_json::to_value(&cluster).unwrap();
+    assert_eq!(json["password"], "hunter2");
+    assert_eq!(json["remote_current_password"], "hunter3");
+}
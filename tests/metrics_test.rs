@@ -0,0 +1,138 @@
+#![cfg(feature = "metrics")]
+
+mod common;
+
+use std::collections::HashMap;
+
+use futon::{layer::MetricsLayer, FutonRequest};
+use futon_core::Layer;
+use http::StatusCode;
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use tower::ServiceExt;
+
+fn send(req: FutonRequest, status: StatusCode) -> HashMap<String, (Vec<String>, DebugValue)> {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+
+    let (service, _) = common::mock_transport(move |_| common::response(status, ""));
+    let service = MetricsLayer::new().layer(service);
+    metrics::with_local_recorder(&recorder, || {
+        futures::executor::block_on(service.oneshot(req)).unwrap()
+    });
+
+    snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .map(|(key, _, _, value)| {
+            let (_, key) = key.into_parts();
+            let labels = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            (key.name().to_string(), (labels, value))
+        })
+        .collect()
+}
+
+#[test]
+fn it_records_request_metrics() {
+    let req = FutonRequest::new("http://couchdb.invalid")
+        .unwrap()
+        .database("films")
+        .segment("_design")
+        .segment("app")
+        .segment("_view")
+        .segment("by_year");
+    let metrics = send(req, StatusCode::OK);
+
+    let (labels, value) = &metrics["futon_requests_total"];
+    assert_eq!(
+        labels,
+        &["method=GET", "endpoint=view", "db=films", "status=200"]
+    );
+    assert_eq!(value, &DebugValue::Counter(1));
+    assert!(matches!(
+        &metrics["futon_request_duration_seconds"].1,
+        DebugValue::Histogram(values) if values.len() == 1
+    ));
+    assert!(!metrics.contains_key("futon_request_errors_total"));
+}
+
+#[test]
+fn it_records_errors() {
+    let req = FutonRequest::new("http://couchdb.invalid")
+        .unwrap()
+        .method("PUT")
+        .unwrap()
+        .database("films")
+        .document("alien", None);
+    let metrics = send(req, StatusCode::CONFLICT);
+
+    let (labels, value) = &metrics["futon_request_errors_total"];
+    assert_eq!(
+        labels,
+        &["method=PUT", "endpoint=document", "db=films", "status=409"]
+    );
+    assert_eq!(value, &DebugValue::Counter(1));
+}
+
+#[test]
+fn it_labels_endpoints() {
+    let cases = [
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .path("_up"),
+            "up",
+        ),
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .path("/"),
+            "server",
+        ),
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .database("films"),
+            "database",
+        ),
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .database("films")
+                .segment("_find"),
+            "find",
+        ),
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .database("films")
+                .partition("sci-fi")
+                .segment("_all_docs"),
+            "all_docs",
+        ),
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .database("films")
+                .document("_design/app", None),
+            "design_document",
+        ),
+        (
+            FutonRequest::new("http://couchdb.invalid")
+                .unwrap()
+                .database("films")
+                .segment("_local")
+                .segment("checkpoint"),
+            "local_document",
+        ),
+    ];
+
+    for (req, endpoint) in cases {
+        let metrics = send(req, StatusCode::OK);
+        let (labels, _) = &metrics["futon_requests_total"];
+        assert_eq!(labels[1], format!("endpoint={endpoint}"));
+    }
+}
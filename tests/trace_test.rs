@@ -0,0 +1,130 @@
+#![cfg(feature = "opentelemetry")]
+
+mod common;
+
+use futon::{layer::TraceContextLayer, FutonRequest};
+use futon_core::Layer;
+use http::StatusCode;
+use opentelemetry::{
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{TraceContextExt, TracerProvider},
+    KeyValue,
+};
+use opentelemetry_sdk::{
+    propagation::TraceContextPropagator,
+    trace::{InMemorySpanExporter, SdkTracerProvider},
+};
+use tower::ServiceExt;
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::prelude::*;
+
+#[tokio::test]
+async fn it_propagates_the_trace_context() {
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("futon")));
+
+    let (service, requests) = common::mock_transport(|_| common::response(StatusCode::OK, ""));
+    let service = TraceContextLayer::new().layer(service);
+    let req = FutonRequest::new("http://couchdb.invalid")
+        .unwrap()
+        .database("films")
+        .document("alien", None);
+
+    let _default = tracing::subscriber::set_default(subscriber);
+    let parent = tracing::info_span!("parent");
+    service
+        .clone()
+        .oneshot(req)
+        .instrument(parent.clone())
+        .await
+        .unwrap();
+    let trace_id = parent.context().span().span_context().trace_id();
+    drop(parent);
+
+    let traceparent = requests.lock().unwrap()[0]
+        .headers()
+        .get("traceparent")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_string();
+    let parts: Vec<_> = traceparent.split('-').collect();
+    assert_eq!(parts.len(), 4);
+    assert_eq!(parts[0], "00");
+    assert_eq!(parts[1], format!("{trace_id:032x}"));
+    assert_eq!(parts[3], "01");
+
+    let spans = exporter.get_finished_spans().unwrap();
+    let span = spans
+        .iter()
+        .find(|span| span.name == "GET document")
+        .unwrap();
+    assert_eq!(parts[2], format!("{:016x}", span.span_context.span_id()));
+    for attribute in [
+        KeyValue::new("db.system", "couchdb"),
+        KeyValue::new("db.name", "films"),
+        KeyValue::new("db.operation", "document"),
+        KeyValue::new("http.response.status_code", 200),
+    ] {
+        assert!(span.attributes.contains(&attribute), "{attribute:?}");
+    }
+
+    // Any configured propagator is used, not only the W3C one.
+    opentelemetry::global::set_text_map_propagator(TraceIdPropagator);
+    let req = FutonRequest::new("http://couchdb.invalid")
+        .unwrap()
+        .path("_up");
+    service
+        .oneshot(req)
+        .instrument(tracing::info_span!("parent"))
+        .await
+        .unwrap();
+
+    let requests = requests.lock().unwrap();
+    assert!(requests[1].headers().get("x-trace-id").is_some());
+    assert!(requests[1].headers().get("traceparent").is_none());
+}
+
+#[derive(Debug)]
+struct TraceIdPropagator;
+
+impl TextMapPropagator for TraceIdPropagator {
+    fn inject_context(&self, cx: &opentelemetry::Context, injector: &mut dyn Injector) {
+        let trace_id = cx.span().span_context().trace_id();
+        injector.set("x-trace-id", format!("{trace_id:032x}"));
+    }
+
+    fn extract_with_context(
+        &self,
+        cx: &opentelemetry::Context,
+        _extractor: &dyn Extractor,
+    ) -> opentelemetry::Context {
+        cx.clone()
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(&[])
+    }
+}
+
+#[test]
+fn it_skips_propagation_without_an_opentelemetry_subscriber() {
+    let (service, requests) = common::mock_transport(|_| common::response(StatusCode::OK, ""));
+    let service = TraceContextLayer::new().layer(service);
+
+    let req = FutonRequest::new("http://couchdb.invalid")
+        .unwrap()
+        .path("_up");
+    futures::executor::block_on(service.oneshot(req)).unwrap();
+
+    assert!(requests.lock().unwrap()[0]
+        .headers()
+        .get("traceparent")
+        .is_none());
+}